@@ -1,13 +1,13 @@
 use dioxus::prelude::*;
 use qrmonsters_avatar::{rarity_color, ColorScheme};
-use qrmonsters_core::{Monster, Rarity};
+use qrmonsters_core::{Monster, Rarity, Trainer};
 
 use super::{MonsterAvatar, StatBar};
 
 /// Get Tailwind classes for rarity glow effect
 fn rarity_glow_class(rarity: Rarity) -> &'static str {
     match rarity {
-        Rarity::Common => "",
+        Rarity::Common | Rarity::Unknown(_) => "",
         Rarity::Rare => "ring-2 ring-blue-500/50 shadow-lg shadow-blue-500/20",
         Rarity::Epic => "ring-2 ring-purple-500/50 shadow-lg shadow-purple-500/30",
         Rarity::Legendary => "ring-2 ring-yellow-400/70 shadow-xl shadow-yellow-500/40 animate-pulse",
@@ -22,10 +22,15 @@ pub fn MonsterCard(
     /// Whether to show a compact version (no stats)
     #[props(default = false)]
     compact: bool,
+    /// Trainer whose signature on the share code verified
+    #[props(!optional, default)]
+    verified_by: Option<Trainer>,
 ) -> Element {
     let colors = ColorScheme::from(monster.element);
     let rarity_col = rarity_color(monster.rarity);
     let glow_class = rarity_glow_class(monster.rarity);
+    // names are self-chosen, so show the key fingerprint to tell trainers apart
+    let verified = verified_by.map(|t| format!("{} · {}", t.name, t.fingerprint()));
 
     rsx! {
         div {
@@ -62,6 +67,13 @@ pub fn MonsterCard(
                             class: "px-2 py-0.5 rounded-full text-xs font-medium bg-slate-700/50 text-slate-300",
                             "{monster.archetype:?}"
                         }
+                        // Signature badge
+                        if let Some(trainer) = verified {
+                            span {
+                                class: "px-2 py-0.5 rounded-full text-xs font-medium bg-emerald-600/30 text-emerald-300",
+                                "✔ Verified from {trainer}"
+                            }
+                        }
                     }
                 }
             }
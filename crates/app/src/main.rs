@@ -5,8 +5,10 @@ mod screens;
 mod storage;
 
 // Re-export screen components for router
-pub use screens::{Battle, Home, Import};
+pub use screens::{Battle, Duel, Home, Import, TeamBattle, TeamBattlePicker};
 pub use screens::MonsterDetail as Monster;
+pub use screens::TeamShare as Team;
+pub use screens::TypeChartHelp as Types;
 
 #[derive(Clone, Routable, Debug, PartialEq)]
 pub enum Route {
@@ -19,8 +21,23 @@ pub enum Route {
     #[route("/import")]
     Import {},
 
+    #[route("/team")]
+    Team {},
+
+    #[route("/types")]
+    Types {},
+
     #[route("/battle/:a/:b")]
     Battle { a: String, b: String },
+
+    #[route("/duel/:a/:b")]
+    Duel { a: String, b: String },
+
+    #[route("/team-battle")]
+    TeamBattlePicker {},
+
+    #[route("/team-battle/:a/:b")]
+    TeamBattle { a: String, b: String },
 }
 
 fn main() {
@@ -1,7 +1,10 @@
 use dioxus::prelude::*;
 use uuid::Uuid;
 
-use qrmonsters_core::battle;
+use qrmonsters_core::{
+    battle_with, encode_replay, log_line, BattleConfig, BattleEvent, BattleOutcome, BattleReplay,
+    BattleRules,
+};
 use qrmonsters_avatar::ColorScheme;
 
 use crate::components::MonsterAvatar;
@@ -12,6 +15,13 @@ use crate::Route;
 pub fn Battle(a: String, b: String) -> Element {
     let mut state = use_signal(storage::load);
     let mut battle_saved = use_signal(|| false);
+    let mut replay_code = use_signal(|| None::<String>);
+    // pin the day when the screen opens, so a re-render after midnight
+    // doesn't change the fight
+    let config = use_signal(|| BattleConfig {
+        rules: BattleRules::standard(),
+        ..BattleConfig::today()
+    });
 
     let a_id = Uuid::parse_str(&a).ok();
     let b_id = Uuid::parse_str(&b).ok();
@@ -58,29 +68,49 @@ pub fn Battle(a: String, b: String) -> Element {
         };
     };
 
-    let res = battle(&ma, &mb);
+    let res = battle_with(&ma, &mb, &config.read());
 
     // Save battle to history (once)
     if !*battle_saved.read() {
+        let code = encode_replay(&BattleReplay::new(&ma, &mb, &config.read().rules, &res)).ok();
         let summary = BattleSummary {
             a: ma.id,
             b: mb.id,
-            winner: res.winner,
+            winner: res.winner(),
             turns: res.turns,
+            outcome: Some(res.outcome),
+            seed: Some(res.seed),
+            rules_hash: Some(res.rules_hash),
+            replay: code.clone(),
         };
         state.write().history.insert(0, summary);
         state.write().history.truncate(25);
         storage::save(&state.read());
+        replay_code.set(code);
         battle_saved.set(true);
     }
+    let replay = replay_code.read().clone();
 
-    let (winner, loser) = if res.winner == ma.id {
-        (&ma, &mb)
-    } else {
+    let (winner, loser) = if res.winner() == Some(mb.id) {
         (&mb, &ma)
+    } else {
+        (&ma, &mb)
     };
 
     let winner_colors = ColorScheme::from(winner.element);
+    let how = match res.outcome {
+        BattleOutcome::Knockout { .. } => "Knocked out",
+        BattleOutcome::Points { .. } => "Won on points against",
+        BattleOutcome::Tiebreak { .. } => "Won the tiebreak against",
+        BattleOutcome::Draw => "",
+    };
+    // one entry per log line, from the typed events rather than the text
+    let crits: Vec<bool> = res
+        .events
+        .iter()
+        .filter(|e| log_line(e, &[]).is_some())
+        .map(|e| matches!(e, BattleEvent::Damage { crit: true, .. }))
+        .collect();
 
     rsx! {
         div { class: "space-y-6",
@@ -138,33 +168,48 @@ pub fn Battle(a: String, b: String) -> Element {
             }
 
             // Winner Banner
-            div {
-                class: "bg-gradient-to-r from-yellow-600/20 via-amber-500/20 to-yellow-600/20 rounded-2xl p-6
-                        border-2 border-yellow-500/50 text-center space-y-4",
-                style: "border-color: {winner_colors.primary};",
-
-                div { class: "flex items-center justify-center gap-2",
-                    span { class: "text-3xl", "🏆" }
-                    h2 { class: "text-2xl font-bold text-yellow-400", "Winner!" }
-                    span { class: "text-3xl", "🏆" }
+            if res.outcome == BattleOutcome::Draw {
+                div { class: "bg-slate-800/50 rounded-2xl p-6 border-2 border-slate-500/50 text-center space-y-2",
+                    div { class: "flex items-center justify-center gap-2",
+                        span { class: "text-3xl", "🤝" }
+                        h2 { class: "text-2xl font-bold text-slate-200", "Draw!" }
+                        span { class: "text-3xl", "🤝" }
+                    }
+                    p { class: "text-slate-300",
+                        "Neither monster could get ahead after "
+                        span { class: "font-bold text-purple-400", "{res.turns}" }
+                        " turns"
+                    }
                 }
+            } else {
+                div {
+                    class: "bg-gradient-to-r from-yellow-600/20 via-amber-500/20 to-yellow-600/20 rounded-2xl p-6
+                            border-2 border-yellow-500/50 text-center space-y-4",
+                    style: "border-color: {winner_colors.primary};",
 
-                div { class: "flex justify-center",
-                    div { class: "animate-bounce",
-                        MonsterAvatar { monster: winner.clone(), size: 100 }
+                    div { class: "flex items-center justify-center gap-2",
+                        span { class: "text-3xl", "🏆" }
+                        h2 { class: "text-2xl font-bold text-yellow-400", "Winner!" }
+                        span { class: "text-3xl", "🏆" }
                     }
-                }
 
-                h3 { class: "text-3xl font-extrabold bg-gradient-to-r from-yellow-400 to-amber-400 bg-clip-text text-transparent",
-                    "{winner.name}"
-                }
+                    div { class: "flex justify-center",
+                        div { class: "animate-bounce",
+                            MonsterAvatar { monster: winner.clone(), size: 100 }
+                        }
+                    }
+
+                    h3 { class: "text-3xl font-extrabold bg-gradient-to-r from-yellow-400 to-amber-400 bg-clip-text text-transparent",
+                        "{winner.name}"
+                    }
 
-                p { class: "text-slate-300",
-                    "Defeated "
-                    span { class: "font-semibold text-red-400", "{loser.name}" }
-                    " in "
-                    span { class: "font-bold text-purple-400", "{res.turns}" }
-                    " turns"
+                    p { class: "text-slate-300",
+                        "{how} "
+                        span { class: "font-semibold text-red-400", "{loser.name}" }
+                        " in "
+                        span { class: "font-bold text-purple-400", "{res.turns}" }
+                        " turns"
+                    }
                 }
             }
 
@@ -179,14 +224,39 @@ pub fn Battle(a: String, b: String) -> Element {
                     for (i, line) in res.log.iter().enumerate() {
                         div {
                             key: "{i}",
-                            class: "bg-slate-900/50 rounded-lg px-4 py-2 text-sm text-slate-300 font-mono
-                                    border-l-2 border-purple-500/50",
+                            class: if crits.get(i).copied().unwrap_or(false) {
+                                "bg-slate-900/50 rounded-lg px-4 py-2 text-sm text-amber-300 font-mono
+                                 border-l-2 border-amber-400"
+                            } else {
+                                "bg-slate-900/50 rounded-lg px-4 py-2 text-sm text-slate-300 font-mono
+                                 border-l-2 border-purple-500/50"
+                            },
                             "{line.0}"
                         }
                     }
                 }
             }
 
+            // Replay code
+            if let Some(code) = replay {
+                section { class: "bg-slate-800/50 backdrop-blur-sm rounded-2xl p-6 space-y-4",
+                    h3 { class: "text-xl font-bold text-white flex items-center gap-2",
+                        span { class: "text-2xl", "🎞️" }
+                        "Replay"
+                    }
+                    textarea {
+                        class: "w-full bg-slate-900/50 border border-slate-700 rounded-xl p-3 text-xs font-mono
+                                text-slate-300 resize-none focus:outline-none focus:ring-2 focus:ring-purple-500/50",
+                        readonly: true,
+                        rows: "3",
+                        "{code}"
+                    }
+                    p { class: "text-center text-sm text-slate-400",
+                        "Share this code so anyone can check this fight in Import, on any day."
+                    }
+                }
+            }
+
             // Action button
             div { class: "flex justify-center",
                 Link {
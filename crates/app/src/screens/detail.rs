@@ -1,15 +1,29 @@
 use dioxus::prelude::*;
 use uuid::Uuid;
 
-use qrmonsters_core::encode_share;
+use qrmonsters_core::{encode_share, encode_signed_share, encode_words, Move, MoveKind};
 
 use crate::components::{MonsterCard, QrDisplay};
 use crate::storage;
 use crate::Route;
 
+#[derive(Clone, Copy, PartialEq)]
+enum ShareMode {
+    Qr,
+    Words,
+}
+
 #[component]
 pub fn MonsterDetail(id: String) -> Element {
-    let state = use_signal(storage::load);
+    let state = use_signal(|| {
+        let mut state = storage::load();
+        if state.trainer.is_none() {
+            state.trainer_keys();
+            storage::save(&state);
+        }
+        state
+    });
+    let mut share_mode = use_signal(|| ShareMode::Qr);
 
     let uuid = Uuid::parse_str(&id).ok();
     let mon = uuid.and_then(|u| {
@@ -38,7 +52,19 @@ pub fn MonsterDetail(id: String) -> Element {
         };
     };
 
-    let share = encode_share(&mon).unwrap_or_else(|e| format!("ERR: {e}"));
+    // Monsters we generated are signed with our trainer key; imported ones are
+    // re-shared as plain codes since we can't vouch for them.
+    let is_mine = state.read().my.iter().any(|m| m.id == mon.id);
+    let keys = state.read().trainer.clone();
+    let share = match (&keys, is_mine) {
+        (Some(keys), true) => encode_signed_share(&mon, keys),
+        _ => encode_share(&mon),
+    }
+    .unwrap_or_else(|e| format!("ERR: {e}"));
+    let words = encode_words(&mon).unwrap_or_else(|e| format!("ERR: {e}"));
+    let signed_as = keys.filter(|_| is_mine).map(|k| k.name);
+    let verified_by = state.read().signers.get(&mon.id).cloned();
+    let moves = mon.move_set();
 
     // Get all monsters that can be battled (all monsters except this one)
     let battle_opponents: Vec<_> = state
@@ -60,7 +86,27 @@ pub fn MonsterDetail(id: String) -> Element {
             }
 
             // Monster Card
-            MonsterCard { monster: mon.clone() }
+            MonsterCard { monster: mon.clone(), verified_by: verified_by.clone() }
+
+            // Moves Section
+            section { class: "bg-slate-800/50 backdrop-blur-sm rounded-2xl p-6 space-y-3",
+                h3 { class: "text-xl font-bold text-white flex items-center gap-2",
+                    span { class: "text-2xl", "✨" }
+                    "Moves"
+                }
+                div { class: "grid grid-cols-2 gap-2",
+                    for mv in moves.iter() {
+                        div {
+                            key: "{mv.name}",
+                            class: "bg-slate-900/50 border border-slate-700 rounded-xl px-3 py-2",
+                            p { class: "font-semibold text-white", "{mv.name}" }
+                            p { class: "text-xs text-slate-400",
+                                {move_summary(mv)}
+                            }
+                        }
+                    }
+                }
+            }
 
             // Share Section
             section { class: "bg-slate-800/50 backdrop-blur-sm rounded-2xl p-6 space-y-4",
@@ -69,22 +115,64 @@ pub fn MonsterDetail(id: String) -> Element {
                     "Share"
                 }
 
-                // QR Code
-                div { class: "flex justify-center",
-                    QrDisplay { data: share.clone(), size: 200 }
+                // Tab bar
+                div { class: "flex bg-slate-900/50 rounded-xl p-1",
+                    button {
+                        class: if *share_mode.read() == ShareMode::Qr {
+                            "flex-1 py-2 px-4 rounded-lg font-medium transition-all duration-200 bg-purple-600 text-white shadow-lg"
+                        } else {
+                            "flex-1 py-2 px-4 rounded-lg font-medium transition-all duration-200 text-slate-400 hover:text-white"
+                        },
+                        onclick: move |_| share_mode.set(ShareMode::Qr),
+                        "🔳 QR Code"
+                    }
+                    button {
+                        class: if *share_mode.read() == ShareMode::Words {
+                            "flex-1 py-2 px-4 rounded-lg font-medium transition-all duration-200 bg-purple-600 text-white shadow-lg"
+                        } else {
+                            "flex-1 py-2 px-4 rounded-lg font-medium transition-all duration-200 text-slate-400 hover:text-white"
+                        },
+                        onclick: move |_| share_mode.set(ShareMode::Words),
+                        "🗣️ Words"
+                    }
                 }
 
-                // Share code text
-                textarea {
-                    class: "w-full bg-slate-900/50 border border-slate-700 rounded-xl p-3 text-sm font-mono
-                            text-slate-300 resize-none focus:outline-none focus:ring-2 focus:ring-purple-500/50",
-                    readonly: true,
-                    rows: "3",
-                    "{share}"
-                }
+                match *share_mode.read() {
+                    ShareMode::Qr => rsx! {
+                        // QR Code
+                        div { class: "flex justify-center",
+                            QrDisplay { data: share.clone(), size: 200 }
+                        }
+
+                        // Share code text
+                        textarea {
+                            class: "w-full bg-slate-900/50 border border-slate-700 rounded-xl p-3 text-sm font-mono
+                                    text-slate-300 resize-none focus:outline-none focus:ring-2 focus:ring-purple-500/50",
+                            readonly: true,
+                            rows: "3",
+                            "{share}"
+                        }
+
+                        p { class: "text-center text-sm text-slate-400",
+                            "Scan this QR code or copy the text to share your monster!"
+                        }
+
+                        if let Some(name) = signed_as.clone() {
+                            p { class: "text-center text-xs text-emerald-400",
+                                "🔏 Signed as {name}"
+                            }
+                        }
+                    },
+                    ShareMode::Words => rsx! {
+                        p { class: "bg-slate-900/50 border border-slate-700 rounded-xl p-4 text-lg leading-relaxed
+                                    text-slate-200 select-all",
+                            "{words}"
+                        }
 
-                p { class: "text-center text-sm text-slate-400",
-                    "Scan this QR code or copy the text to share your monster!"
+                        p { class: "text-center text-sm text-slate-400",
+                            "Read these words out loud, or type them into Import on another device."
+                        }
+                    },
                 }
             }
 
@@ -103,19 +191,31 @@ pub fn MonsterDetail(id: String) -> Element {
                 } else {
                     div { class: "space-y-2",
                         for opp in battle_opponents.iter() {
-                            Link {
-                                key: "{opp.id}",
-                                class: "flex items-center justify-between w-full bg-gradient-to-r from-red-600/20 to-orange-600/20
-                                        hover:from-red-600/30 hover:to-orange-600/30 border border-red-500/30
-                                        rounded-xl px-4 py-3 transition-all duration-200 group",
-                                to: Route::Battle {
-                                    a: mon.id.to_string(),
-                                    b: opp.id.to_string(),
-                                },
-                                span { class: "font-semibold text-white group-hover:text-red-300 transition-colors",
-                                    "Battle vs {opp.name}"
+                            div { key: "{opp.id}", class: "flex gap-2",
+                                Link {
+                                    class: "flex-1 flex items-center justify-between bg-gradient-to-r from-red-600/20 to-orange-600/20
+                                            hover:from-red-600/30 hover:to-orange-600/30 border border-red-500/30
+                                            rounded-xl px-4 py-3 transition-all duration-200 group",
+                                    to: Route::Battle {
+                                        a: mon.id.to_string(),
+                                        b: opp.id.to_string(),
+                                    },
+                                    span { class: "font-semibold text-white group-hover:text-red-300 transition-colors",
+                                        "Battle vs {opp.name}"
+                                    }
+                                    span { class: "text-2xl group-hover:scale-110 transition-transform", "⚔️" }
+                                }
+                                // both players pick moves on this device
+                                Link {
+                                    class: "flex items-center gap-2 bg-slate-700 hover:bg-slate-600 text-white font-semibold
+                                            rounded-xl px-4 py-3 transition-all duration-200",
+                                    to: Route::Duel {
+                                        a: mon.id.to_string(),
+                                        b: opp.id.to_string(),
+                                    },
+                                    span { class: "text-xl", "🎮" }
+                                    "Duel"
                                 }
-                                span { class: "text-2xl group-hover:scale-110 transition-transform", "⚔️" }
                             }
                         }
                     }
@@ -124,3 +224,16 @@ pub fn MonsterDetail(id: String) -> Element {
         }
     }
 }
+
+fn move_summary(mv: &Move) -> String {
+    let effect = match mv.kind {
+        MoveKind::Attack => format!("Attack +{}", mv.power),
+        MoveKind::Guard => format!("Guard +{} DEF", mv.power),
+        MoveKind::Heal => format!("Heal {} HP", mv.power),
+        MoveKind::Special => format!("Special +{}", mv.power),
+    };
+    match mv.uses {
+        Some(n) => format!("{effect} · {n}× per battle"),
+        None => effect,
+    }
+}
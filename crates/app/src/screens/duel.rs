@@ -0,0 +1,237 @@
+use dioxus::prelude::*;
+use uuid::Uuid;
+
+use qrmonsters_core::{
+    encode_replay, format_log, Action, BattleConfig, BattleOutcome, BattleReplay, BattleRules,
+    BattleState, MemberSnapshot, Monster, TeamSide,
+};
+
+use crate::components::MonsterAvatar;
+use crate::storage;
+use crate::Route;
+
+/// Hot-seat battle: two players on one device pick each move in turn
+#[component]
+pub fn Duel(a: String, b: String) -> Element {
+    let state = use_signal(storage::load);
+
+    let find = |id: &str| {
+        let id = Uuid::parse_str(id).ok()?;
+        state
+            .read()
+            .my
+            .iter()
+            .chain(state.read().imported.iter())
+            .find(|m| m.id == id)
+            .cloned()
+    };
+    let fighters = find(&a).zip(find(&b));
+
+    // pin the day when the screen opens, like the auto battle screen
+    let config = use_signal(|| BattleConfig {
+        rules: BattleRules::standard(),
+        ..BattleConfig::today()
+    });
+    let mut duel = use_signal({
+        let fighters = fighters.clone();
+        move || {
+            fighters
+                .as_ref()
+                .map(|(ma, mb)| BattleState::new(ma, mb, &config.read()))
+        }
+    });
+
+    let Some((ma, mb)) = fighters else {
+        return rsx! {
+            div { class: "space-y-6",
+                Link {
+                    class: "inline-flex items-center gap-2 text-purple-400 hover:text-purple-300 transition-colors",
+                    to: Route::Home {},
+                    "← Back"
+                }
+                div { class: "bg-slate-800/50 rounded-2xl p-8 text-center",
+                    h1 { class: "text-2xl font-bold text-red-400 mb-2", "Monsters not found" }
+                    p { class: "text-slate-400", "One or both monsters could not be found." }
+                }
+            }
+        };
+    };
+    let Some(current) = duel.read().clone() else {
+        return rsx! {};
+    };
+
+    let snapshot = current.snapshot();
+    let log = format_log(current.events(), &[&ma, &mb]);
+    let turn = snapshot.turn;
+    let [hp_a, hp_b] = [&snapshot.sides[0], &snapshot.sides[1]].map(|s| hp_text(&s.members[0]));
+    let [status_a, status_b] =
+        [&snapshot.sides[0], &snapshot.sides[1]].map(|s| status_text(&s.members[0]));
+
+    // whose move it is, and what they can do
+    let up = snapshot.to_act.map(|side| {
+        let (mon, member) = match side {
+            TeamSide::A => (&ma, &snapshot.sides[0].members[0]),
+            TeamSide::B => (&mb, &snapshot.sides[1].members[0]),
+        };
+        let actions: Vec<(Action, String)> = current
+            .legal_actions()
+            .into_iter()
+            .map(|action| (action, action_label(mon, member, action)))
+            .collect();
+        (mon.name.clone(), actions)
+    });
+
+    let result = current.is_finished().then(|| current.clone().into_result());
+    let banner = result.as_ref().map(|res| {
+        let name = |id: Uuid| {
+            if id == ma.id {
+                ma.name.clone()
+            } else {
+                mb.name.clone()
+            }
+        };
+        match res.outcome {
+            BattleOutcome::Knockout { winner } => format!("{} wins by knockout!", name(winner)),
+            BattleOutcome::Points { winner } => format!("{} wins on points!", name(winner)),
+            BattleOutcome::Tiebreak { winner } => format!("{} wins the tiebreak!", name(winner)),
+            BattleOutcome::Draw => "Draw!".to_string(),
+        }
+    });
+
+    // the players' own moves make the replay, so it checks out like any other
+    let replay = result.as_ref().and_then(|res| {
+        encode_replay(&BattleReplay::new(&ma, &mb, &config.read().rules, res)).ok()
+    });
+
+    let restart = {
+        let (ma, mb) = (ma.clone(), mb.clone());
+        move |_| duel.set(Some(BattleState::new(&ma, &mb, &config.read())))
+    };
+
+    rsx! {
+        div { class: "space-y-6",
+            // Header
+            header { class: "flex items-center gap-4",
+                Link {
+                    class: "text-purple-400 hover:text-purple-300 transition-colors",
+                    to: Route::Home {},
+                    "← Back"
+                }
+                h1 { class: "text-2xl font-bold text-white", "🎮 Duel" }
+                span { class: "ml-auto text-slate-400 text-sm", "Turn {turn}" }
+            }
+
+            // Arena
+            div { class: "bg-gradient-to-b from-slate-800/80 to-slate-900/80 rounded-2xl p-6 border border-slate-700/50",
+                div { class: "flex items-center justify-around gap-4",
+                    div { class: "text-center space-y-2",
+                        MonsterAvatar { monster: ma.clone(), size: 80 }
+                        p { class: "font-bold text-white text-lg", "{ma.name}" }
+                        p { class: "text-white", "❤️ {hp_a}" }
+                        p { class: "text-amber-300 text-xs", "{status_a}" }
+                    }
+                    div { class: "bg-gradient-to-r from-red-600 to-orange-600 text-white font-black text-2xl px-4 py-2 rounded-xl",
+                        "VS"
+                    }
+                    div { class: "text-center space-y-2",
+                        MonsterAvatar { monster: mb.clone(), size: 80 }
+                        p { class: "font-bold text-white text-lg", "{mb.name}" }
+                        p { class: "text-white", "❤️ {hp_b}" }
+                        p { class: "text-amber-300 text-xs", "{status_b}" }
+                    }
+                }
+            }
+
+            // Moves for whoever is up
+            if let Some((name, actions)) = up {
+                section { class: "bg-slate-800/50 backdrop-blur-sm rounded-2xl p-6 space-y-4",
+                    h3 { class: "text-xl font-bold text-white", "{name}'s move" }
+                    p { class: "text-slate-400 text-sm", "Pass the device to {name}'s player." }
+                    div { class: "grid grid-cols-2 gap-3",
+                        for (action, label) in actions {
+                            button {
+                                key: "{label}",
+                                class: "bg-gradient-to-r from-purple-600 to-pink-600 hover:from-purple-500 hover:to-pink-500
+                                        text-white font-bold py-3 px-4 rounded-xl transition-all duration-200",
+                                onclick: move |_| {
+                                    if let Some(duel) = duel.write().as_mut() {
+                                        // the buttons only offer legal actions
+                                        let _ = duel.apply(action);
+                                    }
+                                },
+                                "{label}"
+                            }
+                        }
+                    }
+                }
+            }
+
+            if let Some(banner) = banner {
+                div { class: "bg-gradient-to-r from-yellow-600/20 via-amber-500/20 to-yellow-600/20 rounded-2xl p-6
+                              border-2 border-yellow-500/50 text-center space-y-4",
+                    h2 { class: "text-2xl font-bold text-yellow-400", "🏆 {banner}" }
+                    if let Some(code) = replay {
+                        textarea {
+                            class: "w-full bg-slate-900/50 border border-slate-700 rounded-xl p-3 text-xs font-mono
+                                    text-slate-300 resize-none focus:outline-none focus:ring-2 focus:ring-purple-500/50",
+                            readonly: true,
+                            rows: "3",
+                            "{code}"
+                        }
+                    }
+                    button {
+                        class: "bg-slate-700 hover:bg-slate-600 text-white font-bold py-3 px-6 rounded-xl transition-all duration-200",
+                        onclick: restart,
+                        "Rematch"
+                    }
+                }
+            }
+
+            // Battle Log, newest first
+            section { class: "bg-slate-800/50 backdrop-blur-sm rounded-2xl p-6 space-y-4",
+                h3 { class: "text-xl font-bold text-white flex items-center gap-2",
+                    span { class: "text-2xl", "📜" }
+                    "Battle Log"
+                }
+                div { class: "max-h-64 overflow-y-auto space-y-2 pr-2",
+                    for (i, line) in log.iter().enumerate().rev() {
+                        div {
+                            key: "{i}",
+                            class: "bg-slate-900/50 rounded-lg px-4 py-2 text-sm text-slate-300 font-mono
+                                    border-l-2 border-purple-500/50",
+                            "{line.0}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn hp_text(member: &MemberSnapshot) -> String {
+    format!("{}/{}", member.hp, member.max_hp)
+}
+
+fn status_text(member: &MemberSnapshot) -> String {
+    member
+        .statuses
+        .iter()
+        .map(|(status, left)| format!("{status} ({left})"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Button text for `action`: the move's name and any uses left
+fn action_label(mon: &Monster, member: &MemberSnapshot, action: Action) -> String {
+    match action {
+        Action::Move(kind) => {
+            let name = mon.move_set().get(kind).name.clone();
+            match member.uses[kind as usize] {
+                Some(left) => format!("{name} ({left} left)"),
+                None => name,
+            }
+        }
+        // duels are one-on-one, so there's no one to switch to
+        Action::Switch(i) => format!("Switch to #{}", i + 1),
+    }
+}
@@ -1,12 +1,35 @@
 use dioxus::prelude::*;
 use uuid::Uuid;
 
-use qrmonsters_core::generate_monster;
+use qrmonsters_core::{generate_monster, BattleOutcome};
 
 use crate::components::MonsterCard;
-use crate::storage;
+use crate::storage::{self, BattleSummary};
 use crate::Route;
 
+/// One-line description of a past battle, e.g. "Mossy Golem beat Tiny Imp (KO, 7 turns)"
+fn battle_summary_text(b: &BattleSummary, name: impl Fn(Uuid) -> String) -> String {
+    let how = match b.outcome {
+        Some(BattleOutcome::Knockout { .. }) => "KO",
+        Some(BattleOutcome::Points { .. }) => "on points",
+        Some(BattleOutcome::Tiebreak { .. }) => "tiebreak",
+        Some(BattleOutcome::Draw) | None => "",
+    };
+    let turns = b.turns;
+    match b.winner {
+        None => format!("{} drew with {} ({turns} turns)", name(b.a), name(b.b)),
+        Some(w) => {
+            let loser = if w == b.a { b.b } else { b.a };
+            let how = if how.is_empty() {
+                String::new()
+            } else {
+                format!("{how}, ")
+            };
+            format!("{} beat {} ({how}{turns} turns)", name(w), name(loser))
+        }
+    }
+}
+
 #[component]
 pub fn Home() -> Element {
     let mut state = use_signal(storage::load);
@@ -19,6 +42,23 @@ pub fn Home() -> Element {
         storage::save(&state.read());
     };
 
+    let name = move |id: Uuid| {
+        let state = state.read();
+        state
+            .my
+            .iter()
+            .chain(state.imported.iter())
+            .find(|m| m.id == id)
+            .map_or_else(|| "Unknown".to_string(), |m| m.name.clone())
+    };
+    let recent: Vec<String> = state
+        .read()
+        .history
+        .iter()
+        .take(5)
+        .map(|b| battle_summary_text(b, name))
+        .collect();
+
     rsx! {
         div { class: "space-y-6",
             // Header
@@ -47,6 +87,27 @@ pub fn Home() -> Element {
                     span { class: "text-xl", "📥" }
                     "Import"
                 }
+                Link {
+                    class: "bg-slate-700 hover:bg-slate-600 text-white font-bold py-3 px-6 rounded-xl
+                            transition-all duration-200 hover:scale-[1.02] flex items-center gap-2",
+                    to: Route::Team {},
+                    span { class: "text-xl", "👥" }
+                    "Team"
+                }
+                Link {
+                    class: "bg-slate-700 hover:bg-slate-600 text-white font-bold py-3 px-6 rounded-xl
+                            transition-all duration-200 hover:scale-[1.02] flex items-center gap-2",
+                    to: Route::TeamBattlePicker {},
+                    span { class: "text-xl", "🛡️" }
+                    "Team Battle"
+                }
+                Link {
+                    class: "bg-slate-700 hover:bg-slate-600 text-white font-bold py-3 px-6 rounded-xl
+                            transition-all duration-200 hover:scale-[1.02] flex items-center gap-2",
+                    to: Route::Types {},
+                    span { class: "text-xl", "📖" }
+                    "Types"
+                }
             }
 
             // My Monsters Section
@@ -100,7 +161,30 @@ pub fn Home() -> Element {
                                 key: "{m.id}",
                                 class: "block hover:scale-[1.01] transition-transform duration-200",
                                 to: Route::Monster { id: m.id.to_string() },
-                                MonsterCard { monster: m.clone(), compact: true }
+                                MonsterCard {
+                                    monster: m.clone(),
+                                    compact: true,
+                                    verified_by: state.read().signers.get(&m.id).cloned(),
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Recent Battles Section
+            if !recent.is_empty() {
+                section { class: "space-y-4",
+                    h2 { class: "text-xl font-bold text-white flex items-center gap-2",
+                        span { class: "text-2xl", "⚔️" }
+                        "Recent Battles"
+                    }
+                    div { class: "space-y-2",
+                        for (i, line) in recent.iter().enumerate() {
+                            div {
+                                key: "{i}",
+                                class: "bg-slate-800/50 rounded-xl px-4 py-3 text-sm text-slate-300",
+                                "{line}"
                             }
                         }
                     }
@@ -1,8 +1,14 @@
+use std::ops::Range;
+
 use dioxus::prelude::*;
 
-use qrmonsters_core::decode_share;
+use qrmonsters_core::{
+    decode_replay, decode_share_verified, decode_team, decode_words, verify_replay, BattleOutcome,
+    BattleReplay, CodecError, ReplayError, SharedMonster, Team, ValidateError, REPLAY_PREFIX,
+    TEAM_PREFIX,
+};
 
-use crate::components::QrScanner;
+use crate::components::{MonsterCard, QrScanner};
 use crate::storage;
 use crate::Route;
 
@@ -12,37 +18,254 @@ enum ImportMode {
     Scan,
 }
 
+/// Turn a decode failure into something a player can act on
+fn import_error_message(e: &CodecError) -> String {
+    match e {
+        CodecError::TooLong { .. } => {
+            "That's far too long to be a monster code. Check what you pasted.".into()
+        }
+        CodecError::MissingPrefix => {
+            "That doesn't look like a monster code. Share codes start with QRM.".into()
+        }
+        CodecError::UnsupportedVersion(_) => {
+            "This code was made by a newer version of QR Monsters. Update the app to import it."
+                .into()
+        }
+        CodecError::VersionMismatch { .. } => "This code has been tampered with or damaged.".into(),
+        CodecError::Base64(_) => {
+            "The code contains invalid characters. Make sure you copied all of it.".into()
+        }
+        CodecError::BadCharacter { ch, position } => format!(
+            "Unexpected character {ch:?} at position {}. Check for typos.",
+            position + 1
+        ),
+        CodecError::Malformed(_) | CodecError::Deserialize(_) => {
+            "The code is incomplete or damaged. Try copying it again.".into()
+        }
+        CodecError::Serialize(_) => "Something went wrong reading this monster.".into(),
+        CodecError::BadSignature => {
+            "This signed code doesn't match its signature. It may have been edited.".into()
+        }
+        CodecError::Corrupted { region: Some(r) } => format!(
+            "This code is damaged around characters {}–{}. Check for typos or copy it again.",
+            r.start + 1,
+            r.end
+        ),
+        CodecError::Corrupted { region: None } => {
+            "This code is damaged. Check for typos or copy it again.".into()
+        }
+        CodecError::UnknownWord { word, position } => format!(
+            "\"{word}\" (word {}) isn't in the word list. Check the spelling.",
+            position + 1
+        ),
+        CodecError::TeamSize { max, .. } => {
+            format!("Team codes hold between 1 and {max} monsters. This one was edited.")
+        }
+        CodecError::InvalidTrainer(_) => {
+            "This signed code's trainer name isn't allowed. It may have been edited.".into()
+        }
+        CodecError::InvalidRules(_) => {
+            "This replay's battle rules are out of range. The code was edited.".into()
+        }
+        CodecError::Invalid(ValidateError::StatOutOfRange {
+            stat,
+            value,
+            allowed,
+        }) => format!(
+            "This monster has an impossible {} of {value} (must be {}–{}).",
+            stat.to_uppercase(),
+            allowed.start(),
+            allowed.end()
+        ),
+        CodecError::Invalid(ValidateError::NameLength { max, .. }) => {
+            format!("Monster names must be between 1 and {max} characters.")
+        }
+        CodecError::Invalid(ValidateError::NameCharacter(c)) => {
+            format!("This monster's name contains a character that isn't allowed: {c:?}")
+        }
+        CodecError::Invalid(ValidateError::NameSpacing) => {
+            "This monster's name has stray spaces. The code was edited.".into()
+        }
+        CodecError::Invalid(ValidateError::BelowMinimum { stat, value, min }) => format!(
+            "This monster's {} of {value} is below the minimum of {min}.",
+            stat.to_uppercase()
+        ),
+        CodecError::Invalid(ValidateError::TotalOutOfBudget { rarity, .. }) => {
+            format!("This monster's stats are too strong for a {rarity:?}. The code was edited.")
+        }
+        CodecError::Invalid(ValidateError::ImpossibleDistribution { rarity }) => {
+            format!("No {rarity:?} monster can have this stat spread. The code was edited.")
+        }
+        CodecError::Invalid(ValidateError::NonCanonicalIndex { .. }) => {
+            "This code has been tampered with or damaged.".into()
+        }
+        CodecError::Invalid(ValidateError::IndexOutOfRange { .. }) => {
+            "This monster is from a much newer version of QR Monsters. Update the app to import it."
+                .into()
+        }
+    }
+}
+
+/// Re-run a decoded replay code and say whether it holds up
+fn replay_message(replay: &BattleReplay) -> (bool, String) {
+    let res = match verify_replay(replay) {
+        Ok(res) => res,
+        Err(e) => return (false, replay_error_message(&e)),
+    };
+    let (a, b) = (&replay.a.name, &replay.b.name);
+    let name = |id: uuid::Uuid| if id == replay.a.id { a } else { b };
+    let turns = res.turns;
+    let text = match res.outcome {
+        BattleOutcome::Knockout { winner } => {
+            format!(
+                "Replay checks out: {} won by knockout in {turns} turns.",
+                name(winner)
+            )
+        }
+        BattleOutcome::Points { winner } | BattleOutcome::Tiebreak { winner } => {
+            format!(
+                "Replay checks out: {} won after {turns} turns.",
+                name(winner)
+            )
+        }
+        BattleOutcome::Draw => format!("Replay checks out: {a} and {b} drew after {turns} turns."),
+    };
+    (true, text)
+}
+
+fn replay_error_message(e: &ReplayError) -> String {
+    match e {
+        ReplayError::UnsupportedVersion(_) => {
+            "This replay was made by a newer version of QR Monsters. Update the app to check it."
+                .into()
+        }
+        ReplayError::RulesMismatch | ReplayError::InvalidRules(_) => {
+            "This replay's rules were edited.".into()
+        }
+        ReplayError::Invalid(_) => "This replay has an impossible monster in it.".into(),
+        ReplayError::IllegalAction { .. }
+        | ReplayError::ExtraActions { .. }
+        | ReplayError::Unfinished
+        | ReplayError::HashMismatch => {
+            "This replay doesn't play out the way it says. It was edited or damaged.".into()
+        }
+    }
+}
+
 #[component]
 pub fn Import() -> Element {
     let mut state = use_signal(storage::load);
     let mut input = use_signal(String::new);
     let mut msg = use_signal(|| None::<(bool, String)>); // (is_success, message)
     let mut mode = use_signal(|| ImportMode::Paste);
+    // pasted code and the part a checksum flagged, for highlighting
+    let mut damaged = use_signal(|| None::<(String, Range<usize>)>);
+    // decoded team code and which members are ticked for import
+    let mut team = use_signal(|| None::<(Team, Vec<bool>)>);
 
     let mut do_import = move |code: String| {
         let code = code.trim().to_string();
+        damaged.set(None);
+        team.set(None);
         if code.is_empty() {
             msg.set(Some((false, "Please enter a share code".into())));
             return;
         }
 
-        match decode_share(&code) {
-            Ok(mon) => {
+        if code.starts_with(TEAM_PREFIX) {
+            match decode_team(&code) {
+                Ok(t) => {
+                    let picked = vec![true; t.members.len()];
+                    team.set(Some((t, picked)));
+                }
+                Err(e) => {
+                    if let CodecError::Corrupted { region: Some(r) } = &e {
+                        damaged.set(Some((code.clone(), r.clone())));
+                    }
+                    msg.set(Some((false, import_error_message(&e))));
+                }
+            }
+            return;
+        }
+
+        if code.starts_with(REPLAY_PREFIX) {
+            let message = match decode_replay(&code) {
+                Ok(replay) => replay_message(&replay),
+                Err(e) => (false, import_error_message(&e)),
+            };
+            msg.set(Some(message));
+            return;
+        }
+
+        // anything that isn't a QRM code but has several words is a word list
+        let decoded = if !code.starts_with("QRM") && code.split_whitespace().nth(1).is_some() {
+            decode_words(&code).map(|monster| SharedMonster {
+                monster,
+                signer: None,
+            })
+        } else {
+            decode_share_verified(&code)
+        };
+        match decoded {
+            Ok(shared) => {
+                let mon = shared.monster;
                 // de-dupe by id
                 if state.read().my.iter().any(|m| m.id == mon.id)
                     || state.read().imported.iter().any(|m| m.id == mon.id)
                 {
                     msg.set(Some((false, "You already have this monster!".into())));
                 } else {
-                    let name = mon.name.clone();
+                    let text = match &shared.signer {
+                        Some(t) => format!(
+                            "Imported {}, verified from {} (key {})!",
+                            mon.name,
+                            t.name,
+                            t.fingerprint()
+                        ),
+                        None => format!("Imported {}!", mon.name),
+                    };
+                    if let Some(signer) = shared.signer {
+                        state.write().signers.insert(mon.id, signer);
+                    }
                     state.write().imported.insert(0, mon);
                     storage::save(&state.read());
-                    msg.set(Some((true, format!("Imported {}!", name))));
+                    msg.set(Some((true, text)));
                     input.set(String::new());
                 }
             }
-            Err(e) => msg.set(Some((false, format!("Import failed: {e}")))),
+            Err(e) => {
+                if let CodecError::Corrupted { region: Some(r) } = &e {
+                    damaged.set(Some((code.clone(), r.clone())));
+                }
+                msg.set(Some((false, import_error_message(&e))));
+            }
+        }
+    };
+
+    let import_team = move |_| {
+        let Some((t, picked)) = team.read().clone() else {
+            return;
+        };
+        let mut added = 0;
+        let mut skipped = 0;
+        for (mon, _) in t.members.into_iter().zip(picked).filter(|(_, p)| *p) {
+            let have = state.read().my.iter().any(|m| m.id == mon.id)
+                || state.read().imported.iter().any(|m| m.id == mon.id);
+            if have {
+                skipped += 1;
+            } else {
+                state.write().imported.insert(0, mon);
+                added += 1;
+            }
         }
+        storage::save(&state.read());
+        team.set(None);
+        input.set(String::new());
+        let text = match skipped {
+            0 => format!("Imported {added} monsters!"),
+            _ => format!("Imported {added} monsters ({skipped} you already had)."),
+        };
+        msg.set(Some((added > 0, text)));
     };
 
     rsx! {
@@ -86,19 +309,21 @@ pub fn Import() -> Element {
                         div { class: "space-y-4",
                             p { class: "text-slate-400 text-sm",
                                 "Paste a "
-                                span { class: "font-mono text-purple-400", "QRM1:…" }
-                                " share code below"
+                                span { class: "font-mono text-purple-400", "QRM3:…" }
+                                " share code or a monster's words below"
                             }
                             textarea {
                                 class: "w-full bg-slate-900/50 border border-slate-700 rounded-xl p-4 text-sm font-mono
                                         text-white placeholder-slate-500 resize-none focus:outline-none focus:ring-2
                                         focus:ring-purple-500/50 focus:border-transparent transition-all duration-200",
                                 rows: "4",
-                                placeholder: "QRM1:...",
+                                placeholder: "QRM3:...",
                                 value: "{input}",
                                 oninput: move |e| {
                                     input.set(e.value());
                                     msg.set(None);
+                                    damaged.set(None);
+                                    team.set(None);
                                 }
                             }
                             button {
@@ -123,6 +348,49 @@ pub fn Import() -> Element {
                 }
             }
 
+            // Team preview
+            if let Some((t, picked)) = team.read().clone() {
+                section { class: "bg-slate-800/50 backdrop-blur-sm rounded-2xl p-6 space-y-4",
+                    h3 { class: "text-xl font-bold text-white flex items-center gap-2",
+                        span { class: "text-2xl", "👥" }
+                        {t.name.clone().unwrap_or_else(|| "Team".into())}
+                        span { class: "text-sm font-normal text-slate-400 ml-2", "({t.members.len()})" }
+                    }
+                    p { class: "text-slate-400 text-sm", "Pick which monsters to import" }
+                    div { class: "grid gap-3",
+                        for (i, m) in t.members.iter().enumerate() {
+                            label {
+                                key: "{m.id}",
+                                class: "flex items-center gap-3 cursor-pointer",
+                                input {
+                                    r#type: "checkbox",
+                                    class: "w-5 h-5 accent-purple-500",
+                                    checked: picked[i],
+                                    onchange: move |e| {
+                                        if let Some((_, picked)) = team.write().as_mut() {
+                                            picked[i] = e.checked();
+                                        }
+                                    }
+                                }
+                                div { class: "flex-1",
+                                    MonsterCard { monster: m.clone(), compact: true }
+                                }
+                            }
+                        }
+                    }
+                    button {
+                        class: "w-full bg-gradient-to-r from-green-600 to-emerald-600 hover:from-green-500 hover:to-emerald-500
+                                text-white font-bold py-3 px-6 rounded-xl shadow-lg shadow-green-500/25
+                                disabled:opacity-50 disabled:cursor-not-allowed transition-all duration-200
+                                flex items-center justify-center gap-2",
+                        disabled: !picked.iter().any(|p| *p),
+                        onclick: import_team,
+                        span { class: "text-xl", "📥" }
+                        "Import Selected"
+                    }
+                }
+            }
+
             // Message display
             if let Some((is_success, message)) = msg.read().as_ref() {
                 div {
@@ -135,6 +403,21 @@ pub fn Import() -> Element {
                     span { class: "font-medium", "{message}" }
                 }
             }
+
+            // Where the checksum failed
+            if let Some((code, region)) = damaged.read().as_ref() {
+                if let (Some(before), Some(bad), Some(after)) = (
+                    code.get(..region.start),
+                    code.get(region.clone()),
+                    code.get(region.end..),
+                ) {
+                    p { class: "font-mono text-sm break-all bg-slate-900/50 rounded-xl p-4 text-slate-400",
+                        "{before}"
+                        span { class: "text-red-400 underline decoration-wavy", "{bad}" }
+                        "{after}"
+                    }
+                }
+            }
         }
     }
 }
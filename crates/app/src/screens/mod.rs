@@ -4,8 +4,16 @@ mod home;
 mod detail;
 mod import;
 mod battle;
+mod duel;
+mod team;
+mod team_battle;
+mod types;
 
 pub use home::Home;
 pub use detail::MonsterDetail;
 pub use import::Import;
 pub use battle::Battle;
+pub use duel::Duel;
+pub use team::TeamShare;
+pub use team_battle::{TeamBattle, TeamBattlePicker};
+pub use types::TypeChartHelp;
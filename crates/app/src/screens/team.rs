@@ -0,0 +1,127 @@
+use dioxus::prelude::*;
+use uuid::Uuid;
+
+use qrmonsters_core::{encode_team, Team, MAX_TEAM_SIZE};
+
+use crate::components::{MonsterCard, QrDisplay};
+use crate::storage;
+use crate::Route;
+
+#[component]
+pub fn TeamShare() -> Element {
+    let state = use_signal(storage::load);
+    // picked monsters, in the order they were picked
+    let mut picked = use_signal(Vec::<Uuid>::new);
+    let mut name = use_signal(String::new);
+
+    let all: Vec<_> = state
+        .read()
+        .my
+        .iter()
+        .chain(state.read().imported.iter())
+        .cloned()
+        .collect();
+
+    let members: Vec<_> = picked
+        .read()
+        .iter()
+        .filter_map(|id| all.iter().find(|m| m.id == *id).cloned())
+        .collect();
+    let team_name = name.read().trim().to_string();
+    let share = (!members.is_empty()).then(|| {
+        encode_team(&Team {
+            name: (!team_name.is_empty()).then_some(team_name),
+            members,
+        })
+    });
+
+    rsx! {
+        div { class: "space-y-6",
+            // Header
+            header { class: "flex items-center gap-4",
+                Link {
+                    class: "text-purple-400 hover:text-purple-300 transition-colors",
+                    to: Route::Home {},
+                    "← Back"
+                }
+                h1 { class: "text-2xl font-bold text-white", "Share a Team" }
+            }
+
+            // Team builder
+            section { class: "bg-slate-800/50 backdrop-blur-sm rounded-2xl p-6 space-y-4",
+                input {
+                    class: "w-full bg-slate-900/50 border border-slate-700 rounded-xl p-3 text-white
+                            placeholder-slate-500 focus:outline-none focus:ring-2 focus:ring-purple-500/50",
+                    placeholder: "Team name (optional)",
+                    value: "{name}",
+                    oninput: move |e| name.set(e.value()),
+                }
+                p { class: "text-slate-400 text-sm",
+                    "Pick up to {MAX_TEAM_SIZE} monsters ({picked.read().len()} picked)"
+                }
+
+                if all.is_empty() {
+                    div { class: "text-center py-6",
+                        p { class: "text-4xl mb-3", "🎲" }
+                        p { class: "text-slate-400", "Generate or import some monsters first!" }
+                    }
+                } else {
+                    div { class: "grid gap-3",
+                        for m in all.iter() {
+                            label {
+                                key: "{m.id}",
+                                class: "flex items-center gap-3 cursor-pointer",
+                                input {
+                                    r#type: "checkbox",
+                                    class: "w-5 h-5 accent-purple-500",
+                                    checked: picked.read().contains(&m.id),
+                                    disabled: !picked.read().contains(&m.id) && picked.read().len() >= MAX_TEAM_SIZE,
+                                    onchange: {
+                                        let id = m.id;
+                                        move |e: FormEvent| {
+                                            if e.checked() {
+                                                picked.write().push(id);
+                                            } else {
+                                                picked.write().retain(|p| *p != id);
+                                            }
+                                        }
+                                    }
+                                }
+                                div { class: "flex-1",
+                                    MonsterCard { monster: m.clone(), compact: true }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Share Section
+            match share {
+                Some(Ok(code)) => rsx! {
+                    section { class: "bg-slate-800/50 backdrop-blur-sm rounded-2xl p-6 space-y-4",
+                        div { class: "flex justify-center",
+                            QrDisplay { data: code.clone(), size: 240 }
+                        }
+                        textarea {
+                            class: "w-full bg-slate-900/50 border border-slate-700 rounded-xl p-3 text-sm font-mono
+                                    text-slate-300 resize-none focus:outline-none focus:ring-2 focus:ring-purple-500/50",
+                            readonly: true,
+                            rows: "4",
+                            "{code}"
+                        }
+                        p { class: "text-center text-sm text-slate-400",
+                            "Scan this QR code or copy the text to share your whole team!"
+                        }
+                    }
+                },
+                Some(Err(e)) => rsx! {
+                    div { class: "bg-red-500/20 border border-red-500/50 rounded-xl p-4 text-red-400",
+                        "Can't share this team: {e}"
+                    }
+                },
+                None => rsx! {},
+            }
+        }
+    }
+}
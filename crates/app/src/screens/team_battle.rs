@@ -0,0 +1,268 @@
+use dioxus::prelude::*;
+use uuid::Uuid;
+
+use qrmonsters_core::{
+    log_line, team_battle_with, BattleConfig, BattleRules, Monster, TeamOutcome, TeamSide,
+    TEAM_BATTLE_SIZE,
+};
+
+use crate::components::{MonsterAvatar, MonsterCard};
+use crate::storage;
+use crate::Route;
+
+/// Pick two teams from the collection, then fight them
+#[component]
+pub fn TeamBattlePicker() -> Element {
+    let state = use_signal(storage::load);
+    // picked monsters for each side, in the order they were picked
+    let mut picked = use_signal(|| [Vec::<Uuid>::new(), Vec::new()]);
+    let mut side = use_signal(|| 0usize);
+
+    let all: Vec<_> = state
+        .read()
+        .my
+        .iter()
+        .chain(state.read().imported.iter())
+        .cloned()
+        .collect();
+
+    let current = *side.read();
+    let ready = picked.read().iter().all(|team| !team.is_empty());
+    let ids = |team: &[Uuid]| {
+        team.iter()
+            .map(Uuid::to_string)
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    let route = Route::TeamBattle {
+        a: ids(&picked.read()[0]),
+        b: ids(&picked.read()[1]),
+    };
+
+    rsx! {
+        div { class: "space-y-6",
+            // Header
+            header { class: "flex items-center gap-4",
+                Link {
+                    class: "text-purple-400 hover:text-purple-300 transition-colors",
+                    to: Route::Home {},
+                    "← Back"
+                }
+                h1 { class: "text-2xl font-bold text-white", "Team Battle" }
+            }
+
+            section { class: "bg-slate-800/50 backdrop-blur-sm rounded-2xl p-6 space-y-4",
+                // Tab bar
+                div { class: "flex bg-slate-900/50 rounded-xl p-1",
+                    for (i, label) in ["Team A", "Team B"].into_iter().enumerate() {
+                        button {
+                            key: "{label}",
+                            class: if current == i {
+                                "flex-1 py-2 px-4 rounded-lg font-medium transition-all duration-200 bg-purple-600 text-white shadow-lg"
+                            } else {
+                                "flex-1 py-2 px-4 rounded-lg font-medium transition-all duration-200 text-slate-400 hover:text-white"
+                            },
+                            onclick: move |_| side.set(i),
+                            "{label} ({picked.read()[i].len()})"
+                        }
+                    }
+                }
+                p { class: "text-slate-400 text-sm",
+                    "Pick up to {TEAM_BATTLE_SIZE} monsters. The first one picked leads."
+                }
+
+                if all.is_empty() {
+                    div { class: "text-center py-6",
+                        p { class: "text-4xl mb-3", "🎲" }
+                        p { class: "text-slate-400", "Generate or import some monsters first!" }
+                    }
+                } else {
+                    div { class: "grid gap-3",
+                        for m in all.iter() {
+                            label {
+                                key: "{m.id}",
+                                class: "flex items-center gap-3 cursor-pointer",
+                                input {
+                                    r#type: "checkbox",
+                                    class: "w-5 h-5 accent-purple-500",
+                                    checked: picked.read()[current].contains(&m.id),
+                                    // a monster can only fight for one side
+                                    disabled: picked.read()[1 - current].contains(&m.id)
+                                        || (!picked.read()[current].contains(&m.id)
+                                            && picked.read()[current].len() >= TEAM_BATTLE_SIZE),
+                                    onchange: {
+                                        let id = m.id;
+                                        move |e: FormEvent| {
+                                            let mut picked = picked.write();
+                                            if e.checked() {
+                                                picked[current].push(id);
+                                            } else {
+                                                picked[current].retain(|p| *p != id);
+                                            }
+                                        }
+                                    }
+                                }
+                                div { class: "flex-1",
+                                    MonsterCard { monster: m.clone(), compact: true }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if ready {
+                div { class: "flex justify-center",
+                    Link {
+                        class: "bg-gradient-to-r from-red-600 to-orange-600 hover:from-red-500 hover:to-orange-500
+                                text-white font-bold py-3 px-8 rounded-xl shadow-lg shadow-red-500/25
+                                transition-all duration-200 hover:scale-[1.02] flex items-center gap-2",
+                        to: route,
+                        span { class: "text-xl", "⚔️" }
+                        "Fight!"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Fight two teams, given as comma-separated monster IDs
+#[component]
+pub fn TeamBattle(a: String, b: String) -> Element {
+    let state = use_signal(storage::load);
+    // pin the day when the screen opens, like the one-on-one battle screen
+    let config = use_signal(|| BattleConfig {
+        rules: BattleRules::standard(),
+        ..BattleConfig::today()
+    });
+
+    let find_team = |ids: &str| -> Option<Vec<Monster>> {
+        let state = state.read();
+        ids.split(',')
+            .map(|id| {
+                let id = Uuid::parse_str(id).ok()?;
+                state
+                    .my
+                    .iter()
+                    .chain(state.imported.iter())
+                    .find(|m| m.id == id)
+                    .cloned()
+            })
+            .collect()
+    };
+
+    let (Some(team_a), Some(team_b)) = (find_team(&a), find_team(&b)) else {
+        return rsx! {
+            div { class: "space-y-6",
+                Link {
+                    class: "inline-flex items-center gap-2 text-purple-400 hover:text-purple-300 transition-colors",
+                    to: Route::TeamBattlePicker {},
+                    "← Back"
+                }
+                div { class: "bg-slate-800/50 rounded-2xl p-8 text-center",
+                    h1 { class: "text-2xl font-bold text-red-400 mb-2", "Monsters not found" }
+                    p { class: "text-slate-400", "Some of these monsters could not be found." }
+                }
+            }
+        };
+    };
+
+    let res = team_battle_with(&team_a, &team_b, &config.read());
+    let everyone: Vec<&Monster> = team_a.iter().chain(&team_b).collect();
+    let lines: Vec<String> = res
+        .events
+        .iter()
+        .filter_map(|e| log_line(e, &everyone))
+        .map(|line| line.0)
+        .collect();
+
+    let banner = match res.outcome {
+        TeamOutcome::Knockout { winner } => format!("🏆 {} wins by knockout!", side_name(winner)),
+        TeamOutcome::Points { winner } => format!("🏆 {} wins on points!", side_name(winner)),
+        TeamOutcome::Draw => "🤝 Draw!".to_string(),
+    };
+    let rows: Vec<_> = res
+        .members
+        .iter()
+        .filter_map(|r| {
+            let m = everyone.iter().find(|m| m.id == r.monster)?;
+            Some(((*m).clone(), r.clone()))
+        })
+        .collect();
+
+    rsx! {
+        div { class: "space-y-6",
+            // Header
+            header { class: "flex items-center gap-4",
+                Link {
+                    class: "text-purple-400 hover:text-purple-300 transition-colors",
+                    to: Route::TeamBattlePicker {},
+                    "← Back"
+                }
+                h1 { class: "text-2xl font-bold text-white", "⚔️ Team Battle!" }
+            }
+
+            // Result banner
+            div { class: "bg-gradient-to-r from-yellow-600/20 via-amber-500/20 to-yellow-600/20 rounded-2xl p-6
+                        border-2 border-yellow-500/50 text-center space-y-2",
+                h2 { class: "text-2xl font-bold text-yellow-400", "{banner}" }
+                p { class: "text-slate-300",
+                    "After "
+                    span { class: "font-bold text-purple-400", "{res.turns}" }
+                    " turns"
+                }
+            }
+
+            // Per-member results
+            section { class: "bg-slate-800/50 backdrop-blur-sm rounded-2xl p-6 space-y-3",
+                h3 { class: "text-xl font-bold text-white", "Members" }
+                for (m, r) in rows {
+                    div {
+                        key: "{m.id}",
+                        class: "flex items-center gap-3 bg-slate-900/50 rounded-xl px-3 py-2",
+                        MonsterAvatar { monster: m.clone(), size: 40 }
+                        div { class: "flex-1",
+                            p { class: "font-semibold text-white",
+                                "{m.name} "
+                                span { class: "text-xs text-slate-400", {side_name(r.side)} }
+                            }
+                            p { class: "text-xs text-slate-400",
+                                "{r.damage_dealt} damage · {r.knockouts} KOs"
+                            }
+                        }
+                        span {
+                            class: if r.fainted { "text-red-400 line-through" } else { "text-white" },
+                            "❤️ {r.remaining_hp}"
+                        }
+                    }
+                }
+            }
+
+            // Battle Log
+            section { class: "bg-slate-800/50 backdrop-blur-sm rounded-2xl p-6 space-y-4",
+                h3 { class: "text-xl font-bold text-white flex items-center gap-2",
+                    span { class: "text-2xl", "📜" }
+                    "Battle Log"
+                }
+                div { class: "max-h-64 overflow-y-auto space-y-2 pr-2",
+                    for (i, line) in lines.iter().enumerate() {
+                        div {
+                            key: "{i}",
+                            class: "bg-slate-900/50 rounded-lg px-4 py-2 text-sm text-slate-300 font-mono
+                                    border-l-2 border-purple-500/50",
+                            "{line}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn side_name(side: TeamSide) -> &'static str {
+    match side {
+        TeamSide::A => "Team A",
+        TeamSide::B => "Team B",
+    }
+}
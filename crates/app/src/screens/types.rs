@@ -0,0 +1,91 @@
+use dioxus::prelude::*;
+
+use qrmonsters_core::{BattleRules, Effect, Element as MonsterElement, StatusKind};
+
+use crate::Route;
+
+/// Help screen showing the type chart the app's battles use
+#[component]
+pub fn TypeChartHelp() -> Element {
+    // the same chart battle_with reads, so this can't drift from the engine
+    let rules = BattleRules::standard();
+    let chart = rules.type_chart;
+    let bonus = rules.advantage_bonus;
+    let elements = MonsterElement::KNOWN;
+
+    let statuses: Vec<_> = elements
+        .iter()
+        .filter_map(|&e| StatusKind::for_element(e).map(|s| (e, s, s.duration())))
+        .collect();
+
+    let cell = |attacker: MonsterElement, defender: MonsterElement| {
+        let percent = chart.multiplier(attacker, defender);
+        let text = format!("×{}.{:02}", percent / 100, percent % 100);
+        let class = match chart.effect(attacker, defender) {
+            Effect::Super => "bg-emerald-600/30 text-emerald-300",
+            Effect::Neutral => "bg-slate-900/50 text-slate-500",
+            Effect::Resisted => "bg-red-600/30 text-red-300",
+        };
+        (text, class)
+    };
+
+    rsx! {
+        div { class: "space-y-6",
+            // Header
+            header { class: "flex items-center gap-4",
+                Link {
+                    class: "text-purple-400 hover:text-purple-300 transition-colors",
+                    to: Route::Home {},
+                    "← Back"
+                }
+                h1 { class: "text-2xl font-bold text-white", "Type Chart" }
+            }
+
+            section { class: "bg-slate-800/50 backdrop-blur-sm rounded-2xl p-6 space-y-4 overflow-x-auto",
+                p { class: "text-slate-400 text-sm",
+                    "Rows attack, columns defend. Super effective attacks also get +{bonus} to hit."
+                }
+                table { class: "w-full text-center text-sm border-separate border-spacing-1",
+                    thead {
+                        tr {
+                            th {}
+                            for defender in elements {
+                                th { key: "{defender:?}", class: "text-slate-300 font-semibold px-2",
+                                    "{defender:?}"
+                                }
+                            }
+                        }
+                    }
+                    tbody {
+                        for attacker in elements {
+                            tr { key: "{attacker:?}",
+                                th { class: "text-slate-300 font-semibold text-left pr-2", "{attacker:?}" }
+                                for defender in elements {
+                                    {
+                                        let (text, class) = cell(attacker, defender);
+                                        rsx! {
+                                            td { key: "{defender:?}", class: "rounded-lg py-2 font-mono {class}",
+                                                "{text}"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Status effects
+            section { class: "bg-slate-800/50 backdrop-blur-sm rounded-2xl p-6 space-y-3",
+                h3 { class: "text-xl font-bold text-white", "Status Effects" }
+                for (element, status, moves) in statuses {
+                    p { key: "{element:?}", class: "text-slate-300 text-sm",
+                        span { class: "font-semibold text-white", "{element:?}" }
+                        " hits can leave the target {status} for {moves} move(s)."
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,21 +1,57 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use qrmonsters_core::Monster;
+use qrmonsters_core::{BattleOutcome, Monster, Trainer, TrainerKeypair};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AppState {
     pub my: Vec<Monster>,
     pub imported: Vec<Monster>,
     pub history: Vec<BattleSummary>,
+    /// This device's signing identity, created on first use
+    #[serde(default)]
+    pub trainer: Option<TrainerKeypair>,
+    /// Who signed each imported monster, for the ones that came signed
+    #[serde(default)]
+    pub signers: HashMap<Uuid, Trainer>,
+}
+
+impl AppState {
+    /// The local trainer keypair, generating and remembering one if needed.
+    /// Callers should `save` afterwards so the key survives restarts.
+    pub fn trainer_keys(&mut self) -> TrainerKeypair {
+        self.trainer
+            .get_or_insert_with(|| {
+                let mut keys = TrainerKeypair::generate("Trainer").expect("valid name");
+                keys.name = format!("Trainer {}", &keys.trainer().fingerprint()[..4]);
+                keys
+            })
+            .clone()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BattleSummary {
     pub a: Uuid,
     pub b: Uuid,
-    pub winner: Uuid,
+    /// `None` for a draw
+    pub winner: Option<Uuid>,
     pub turns: u32,
+    /// How the battle ended. Missing for battles saved by older builds.
+    #[serde(default)]
+    pub outcome: Option<BattleOutcome>,
+    /// RNG seed the battle ran with, so it can be re-run exactly.
+    /// Missing for battles saved by older builds.
+    #[serde(default)]
+    pub seed: Option<[u8; 32]>,
+    /// `BattleRules::hash` of the rules it was fought under
+    #[serde(default)]
+    pub rules_hash: Option<[u8; 32]>,
+    /// `QRMB:` replay code, which re-runs and checks the battle on any day
+    #[serde(default)]
+    pub replay: Option<String>,
 }
 
 pub fn load() -> AppState {
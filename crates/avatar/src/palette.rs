@@ -48,6 +48,13 @@ impl From<Element> for ColorScheme {
                 highlight: "#FFFF00",
                 shadow: "#8B6914",
             },
+            // Elements from newer builds render in neutral greys
+            Element::Unknown(_) => ColorScheme {
+                primary: "#9CA3AF",
+                secondary: "#6B7280",
+                highlight: "#D1D5DB",
+                shadow: "#374151",
+            },
         }
     }
 }
@@ -68,7 +75,7 @@ pub struct RarityGlow {
 impl From<Rarity> for RarityGlow {
     fn from(rarity: Rarity) -> Self {
         match rarity {
-            Rarity::Common => RarityGlow {
+            Rarity::Common | Rarity::Unknown(_) => RarityGlow {
                 enabled: false,
                 color: "transparent",
                 intensity: 0,
@@ -103,6 +110,7 @@ pub fn rarity_class(rarity: Rarity) -> &'static str {
         Rarity::Rare => "rarity-rare",
         Rarity::Epic => "rarity-epic",
         Rarity::Legendary => "rarity-legendary",
+        Rarity::Unknown(_) => "rarity-unknown",
     }
 }
 
@@ -113,5 +121,6 @@ pub fn rarity_color(rarity: Rarity) -> &'static str {
         Rarity::Rare => "#4A90D9",
         Rarity::Epic => "#9B59B6",
         Rarity::Legendary => "#F1C40F",
+        Rarity::Unknown(_) => "#607D8B",
     }
 }
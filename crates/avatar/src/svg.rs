@@ -25,7 +25,8 @@ pub fn monster_svg(monster: &Monster, size: u32) -> String {
         Archetype::Tank => generate_tank_body(size, var1, var2, &colors),
         Archetype::Assassin => generate_assassin_body(size, var1, var2, &colors),
         Archetype::Mage => generate_mage_body(size, var1, var2, &colors),
-        Archetype::Beast => generate_beast_body(size, var1, var2, &colors),
+        // archetypes from newer builds get the most generic body
+        Archetype::Beast | Archetype::Unknown(_) => generate_beast_body(size, var1, var2, &colors),
     };
 
     // Generate eyes
@@ -106,6 +107,7 @@ fn generate_tank_body(size: u32, var1: f32, var2: f32, colors: &ColorScheme) ->
     )
 }
 
+#[allow(clippy::format_in_format_args)]
 fn generate_assassin_body(size: u32, var1: f32, var2: f32, colors: &ColorScheme) -> String {
     let cx = size as f32 / 2.0;
     let cy = size as f32 / 2.0;
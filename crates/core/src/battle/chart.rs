@@ -0,0 +1,133 @@
+//! Element effectiveness, as data.
+//!
+//! A [`TypeChart`] says how well each element's attacks land on each other
+//! element. Super effective attacks get the rules' advantage bonus to hit,
+//! and both super effective and resisted attacks have their damage scaled.
+//! The app's chart lives in `data/type_chart.json`; event organisers can
+//! ship their own.
+
+use serde::{Deserialize, Serialize};
+
+use crate::Element;
+
+const BALANCED_JSON: &str = include_str!("../../data/type_chart.json");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Effect {
+    Super,
+    Neutral,
+    Resisted,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeEntry {
+    pub attacker: Element,
+    pub defender: Element,
+    pub effect: Effect,
+}
+
+/// Per-pair element effectiveness. Pairs without an entry are neutral, and
+/// so is anything involving an element this build doesn't know.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeChart {
+    /// Damage percentage for super effective attacks
+    pub super_percent: u16,
+    /// Damage percentage for resisted attacks
+    pub resisted_percent: u16,
+    #[serde(default)]
+    pub entries: Vec<TypeEntry>,
+}
+
+impl TypeChart {
+    /// The original table: Fire > Earth > Electric > Water > Fire for +2 to
+    /// hit, Air neutral, no damage scaling
+    pub fn classic() -> Self {
+        use Element::*;
+        TypeChart {
+            super_percent: 100,
+            resisted_percent: 100,
+            entries: [
+                (Fire, Earth),
+                (Earth, Electric),
+                (Electric, Water),
+                (Water, Fire),
+            ]
+            .into_iter()
+            .map(|(attacker, defender)| TypeEntry {
+                attacker,
+                defender,
+                effect: Effect::Super,
+            })
+            .collect(),
+        }
+    }
+
+    /// Every element super effective against two others and resisted by
+    /// them in turn, from `data/type_chart.json`
+    pub fn balanced() -> Self {
+        Self::from_json(BALANCED_JSON).expect("bundled type chart is valid")
+    }
+
+    /// Load a chart from JSON shaped like `data/type_chart.json`
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn effect(&self, attacker: Element, defender: Element) -> Effect {
+        self.entries
+            .iter()
+            .find(|e| e.attacker == attacker && e.defender == defender)
+            .map_or(Effect::Neutral, |e| e.effect)
+    }
+
+    /// Damage percentage for `attacker` hitting `defender`
+    pub fn multiplier(&self, attacker: Element, defender: Element) -> u16 {
+        match self.effect(attacker, defender) {
+            Effect::Super => self.super_percent,
+            Effect::Neutral => 100,
+            Effect::Resisted => self.resisted_percent,
+        }
+    }
+}
+
+impl Default for TypeChart {
+    fn default() -> Self {
+        TypeChart::classic()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_balanced_chart() {
+        let chart = TypeChart::balanced();
+        for el in Element::KNOWN {
+            let count = |effect| {
+                Element::KNOWN
+                    .iter()
+                    .filter(|&&other| chart.effect(el, other) == effect)
+                    .count()
+            };
+            assert_eq!(
+                (count(Effect::Super), count(Effect::Resisted)),
+                (2, 2),
+                "{el:?}"
+            );
+            for other in Element::KNOWN {
+                if chart.effect(el, other) == Effect::Super {
+                    assert_eq!(chart.effect(other, el), Effect::Resisted);
+                }
+            }
+        }
+        assert_eq!(chart.multiplier(Element::Air, Element::Fire), 150);
+        assert_eq!(chart.multiplier(Element::Fire, Element::Unknown(9)), 100);
+
+        let classic = TypeChart::classic();
+        assert!(Element::KNOWN
+            .iter()
+            .all(|&other| classic.effect(Element::Air, other) == Effect::Neutral));
+        assert_eq!(classic.multiplier(Element::Fire, Element::Earth), 100);
+    }
+}
@@ -0,0 +1,367 @@
+//! Typed battle events, and the text log formatted from them.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{BattleOutcome, Effect, StatusKind, TeamOutcome};
+use crate::{Monster, MoveKind};
+
+/// One line of the human-readable battle log
+#[derive(Debug, Clone)]
+pub struct BattleLine(pub String);
+
+/// How an attack landed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HitResult {
+    Hit,
+    /// Half damage, no crit and no status
+    Graze,
+    Miss,
+}
+
+/// Something that happened during a battle, in order.
+///
+/// HP values are clamped at zero, the way they're shown to players.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BattleEvent {
+    TurnStart {
+        turn: u32,
+    },
+    /// Whose action comes next on the [`Initiative::Timeline`](super::Initiative::Timeline)
+    Initiative {
+        turn: u32,
+        monster: Uuid,
+        /// Timeline tick of the action
+        time: u32,
+        /// Both sides were due at once and the battle RNG picked this one
+        tie: bool,
+    },
+    /// A monster picked a move from its [`MoveSet`](crate::MoveSet); only
+    /// under rules with [`moves`](super::BattleRules::moves) on
+    MoveUsed {
+        turn: u32,
+        monster: Uuid,
+        kind: MoveKind,
+        name: String,
+    },
+    /// DEF bonus that lasts until the monster's next move
+    Guard {
+        turn: u32,
+        monster: Uuid,
+        bonus: i16,
+    },
+    Heal {
+        turn: u32,
+        monster: Uuid,
+        amount: i16,
+        hp_before: i16,
+        hp_after: i16,
+    },
+    /// The to-hit roll under [`evasion`](super::BattleRules::evasion); a miss
+    /// ends the attack
+    Accuracy {
+        turn: u32,
+        attacker: Uuid,
+        defender: Uuid,
+        roll: i16,
+        /// Percent chance of a clean hit; the next 15 points graze
+        chance: i16,
+        result: HitResult,
+    },
+    /// Attack and block rolls, before damage is worked out
+    Attack {
+        turn: u32,
+        attacker: Uuid,
+        defender: Uuid,
+        /// The attacker's d20
+        hit_roll: i16,
+        /// `hit_roll` plus ATK, luck, element and move bonuses, less status
+        /// penalties
+        hit: i16,
+        /// The defender's d20
+        block_roll: i16,
+        /// `block_roll` plus DEF, luck and any guard bonus, less status
+        /// penalties
+        block: i16,
+    },
+    /// The attacker's element beats the defender's
+    ElementAdvantage {
+        turn: u32,
+        attacker: Uuid,
+        defender: Uuid,
+        bonus: i16,
+    },
+    /// Damage scaled by the [`TypeChart`](super::TypeChart); only when the
+    /// percentage isn't 100
+    Effectiveness {
+        turn: u32,
+        attacker: Uuid,
+        defender: Uuid,
+        effect: Effect,
+        percent: u16,
+    },
+    /// The crit roll came in under the attacker's CRIT
+    Crit {
+        turn: u32,
+        attacker: Uuid,
+        defender: Uuid,
+        roll: i16,
+        chance: i16,
+    },
+    /// A knockout blow left the defender on 1 HP instead
+    LuckyEscape {
+        turn: u32,
+        monster: Uuid,
+        roll: i16,
+        chance: i16,
+    },
+    Damage {
+        turn: u32,
+        attacker: Uuid,
+        defender: Uuid,
+        amount: i16,
+        crit: bool,
+        hp_before: i16,
+        hp_after: i16,
+    },
+    /// The attacker's element status took hold
+    StatusApplied {
+        turn: u32,
+        monster: Uuid,
+        status: StatusKind,
+        /// How many of the victim's moves it lasts
+        moves: u8,
+    },
+    /// The resist roll came in under the victim's LUCK-based chance
+    StatusResisted {
+        turn: u32,
+        monster: Uuid,
+        status: StatusKind,
+        roll: i16,
+        chance: i16,
+    },
+    /// Damage from a status at the start of the victim's move
+    StatusTick {
+        turn: u32,
+        monster: Uuid,
+        status: StatusKind,
+        amount: i16,
+        hp_before: i16,
+        hp_after: i16,
+    },
+    /// The monster lost its move to a stun
+    Stunned {
+        turn: u32,
+        monster: Uuid,
+    },
+    StatusEnded {
+        turn: u32,
+        monster: Uuid,
+        status: StatusKind,
+    },
+    Faint {
+        turn: u32,
+        monster: Uuid,
+    },
+    /// A team monster came out front
+    SwitchIn {
+        turn: u32,
+        monster: Uuid,
+        replacing: Uuid,
+        /// `replacing` fainted, rather than choosing to switch out
+        forced: bool,
+    },
+    /// Always the last event of a one-on-one battle
+    Outcome {
+        outcome: BattleOutcome,
+        turns: u32,
+    },
+    /// Who a battle royale attacker went after, picked by the battle RNG
+    Target {
+        turn: u32,
+        attacker: Uuid,
+        target: Uuid,
+    },
+    /// A battle royale monster is out, finishing in `place`
+    Eliminated {
+        turn: u32,
+        monster: Uuid,
+        place: u32,
+    },
+    /// Always the last event of a battle royale
+    RoyaleOutcome {
+        /// Everyone, first place first
+        ranking: Vec<Uuid>,
+        turns: u32,
+    },
+    /// Always the last event of a team battle
+    TeamOutcome {
+        outcome: TeamOutcome,
+        turns: u32,
+    },
+}
+
+/// Render `events` as the text log, naming monsters from `monsters`
+pub fn format_log(events: &[BattleEvent], monsters: &[&Monster]) -> Vec<BattleLine> {
+    events
+        .iter()
+        .filter_map(|e| log_line(e, monsters))
+        .collect()
+}
+
+/// The log line for one event, if it gets one
+pub fn log_line(event: &BattleEvent, monsters: &[&Monster]) -> Option<BattleLine> {
+    let name = |id: &Uuid| {
+        monsters
+            .iter()
+            .find(|m| m.id == *id)
+            .map_or("???", |m| m.name.as_str())
+    };
+    let text = match event {
+        BattleEvent::Damage {
+            turn,
+            attacker,
+            defender,
+            amount,
+            crit,
+            hp_before,
+            hp_after,
+        } => format!(
+            "T{turn}: {} hits {} for {amount}{} ({hp_before}→{hp_after})",
+            name(attacker),
+            name(defender),
+            if *crit { " CRIT" } else { "" },
+        ),
+        BattleEvent::MoveUsed {
+            turn,
+            monster,
+            name: move_name,
+            ..
+        } => format!("T{turn}: {} uses {move_name}", name(monster)),
+        BattleEvent::Guard {
+            turn,
+            monster,
+            bonus,
+        } => format!("T{turn}: {} guards (+{bonus} DEF)", name(monster)),
+        BattleEvent::Heal {
+            turn,
+            monster,
+            amount,
+            hp_before,
+            hp_after,
+        } => format!(
+            "T{turn}: {} recovers {amount} HP ({hp_before}→{hp_after})",
+            name(monster)
+        ),
+        BattleEvent::Accuracy {
+            turn,
+            attacker,
+            defender,
+            result,
+            ..
+        } => match result {
+            HitResult::Hit => return None,
+            HitResult::Graze => {
+                format!("T{turn}: {} grazes {}", name(attacker), name(defender))
+            }
+            HitResult::Miss => format!("T{turn}: {} misses {}", name(attacker), name(defender)),
+        },
+        BattleEvent::SwitchIn {
+            turn,
+            monster,
+            replacing,
+            forced: true,
+        } => format!(
+            "T{turn}: {} comes in for {}",
+            name(monster),
+            name(replacing)
+        ),
+        BattleEvent::SwitchIn {
+            turn,
+            monster,
+            replacing,
+            forced: false,
+        } => format!(
+            "T{turn}: {} switches out for {}",
+            name(replacing),
+            name(monster)
+        ),
+        BattleEvent::Target {
+            turn,
+            attacker,
+            target,
+        } => format!("T{turn}: {} goes after {}", name(attacker), name(target)),
+        BattleEvent::Eliminated {
+            turn,
+            monster,
+            place,
+        } => format!("T{turn}: {} is out in {}", name(monster), ordinal(*place)),
+        BattleEvent::LuckyEscape { turn, monster, .. } => {
+            format!("T{turn}: {} hangs on with 1 HP!", name(monster))
+        }
+        BattleEvent::Effectiveness {
+            turn,
+            effect,
+            percent,
+            ..
+        } => format!(
+            "T{turn}: {} (×{}.{:02})",
+            match effect {
+                Effect::Super => "It's super effective",
+                Effect::Neutral => "It's a normal hit",
+                Effect::Resisted => "It's not very effective",
+            },
+            percent / 100,
+            percent % 100
+        ),
+        BattleEvent::StatusApplied {
+            turn,
+            monster,
+            status,
+            moves,
+        } => format!(
+            "T{turn}: {} is {status} ({moves} move{})",
+            name(monster),
+            if *moves == 1 { "" } else { "s" }
+        ),
+        BattleEvent::StatusResisted {
+            turn,
+            monster,
+            status,
+            ..
+        } => format!("T{turn}: {} resists being {status}", name(monster)),
+        BattleEvent::StatusTick {
+            turn,
+            monster,
+            status,
+            amount,
+            hp_before,
+            hp_after,
+        } => format!(
+            "T{turn}: {} takes {amount} {status} damage ({hp_before}→{hp_after})",
+            name(monster)
+        ),
+        BattleEvent::Stunned { turn, monster } => {
+            format!("T{turn}: {} is stunned and can't move", name(monster))
+        }
+        BattleEvent::StatusEnded {
+            turn,
+            monster,
+            status,
+        } => format!("T{turn}: {} is no longer {status}", name(monster)),
+        _ => return None,
+    };
+    Some(BattleLine(text))
+}
+
+/// 1st, 2nd, 3rd, 4th, ... 11th, 12th, 13th, ... 21st
+pub fn ordinal(n: u32) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{n}{suffix}")
+}
@@ -0,0 +1,1133 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{Monster, Move, MoveKind, MoveSet};
+
+mod chart;
+mod event;
+mod replay;
+mod royale;
+mod rules;
+mod state;
+mod status;
+mod strategy;
+mod team;
+
+pub use chart::*;
+pub use event::*;
+pub use replay::*;
+pub use royale::*;
+pub use rules::*;
+pub use state::*;
+pub use status::*;
+pub use strategy::*;
+pub use team::*;
+
+/// How a battle ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BattleOutcome {
+    /// The loser's HP reached zero
+    Knockout { winner: Uuid },
+    /// The turn cap was reached and `winner` had more HP left
+    Points { winner: Uuid },
+    /// The turn cap was reached level on HP and `winner` took the [`TieBreak`]
+    Tiebreak { winner: Uuid },
+    /// The turn cap was reached with nothing to separate them
+    Draw,
+}
+
+impl BattleOutcome {
+    pub fn winner(&self) -> Option<Uuid> {
+        match *self {
+            BattleOutcome::Knockout { winner }
+            | BattleOutcome::Points { winner }
+            | BattleOutcome::Tiebreak { winner } => Some(winner),
+            BattleOutcome::Draw => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BattleResult {
+    pub outcome: BattleOutcome,
+    pub turns: u32,
+    /// Everything that happened, for animating or charting the fight
+    pub events: Vec<BattleEvent>,
+    /// The events as text, from [`format_log`]
+    pub log: Vec<BattleLine>,
+    pub a_remaining_hp: i16,
+    pub b_remaining_hp: i16,
+    /// Every action taken, A's and B's interleaved; with the seed and rules
+    /// that's enough to replay the battle
+    pub actions: Vec<Action>,
+    /// RNG seed the battle ran with; [`BattleConfig::with_seed`] replays it
+    pub seed: [u8; 32],
+    /// [`BattleRules::hash`] of the rules the battle ran under
+    pub rules_hash: [u8; 32],
+}
+
+/// Where a battle's RNG seed comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BattleSeed {
+    /// Mix both monster IDs with a day number (days since the Unix epoch), so
+    /// the same pair fights the same way all day and differently tomorrow
+    Day(u32),
+    /// Use this seed as-is
+    Fixed([u8; 32]),
+}
+
+/// Everything besides the two monsters that decides how a battle plays out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BattleConfig {
+    pub seed: BattleSeed,
+    pub rules: BattleRules,
+}
+
+impl BattleConfig {
+    /// Today's battle, going by this device's clock (UTC)
+    pub fn today() -> Self {
+        Self::on_day(current_day())
+    }
+
+    /// The battle two monsters would have fought on `day`
+    pub fn on_day(day: u32) -> Self {
+        BattleConfig {
+            seed: BattleSeed::Day(day),
+            rules: BattleRules::classic(),
+        }
+    }
+
+    /// Re-run a battle from a recorded [`BattleResult::seed`]
+    pub fn with_seed(seed: [u8; 32]) -> Self {
+        BattleConfig {
+            seed: BattleSeed::Fixed(seed),
+            rules: BattleRules::classic(),
+        }
+    }
+
+    /// The RNG seed this config gives a battle between `a` and `b`
+    pub fn seed_for(&self, a: Uuid, b: Uuid) -> [u8; 32] {
+        self.seed_for_all(&[a, b])
+    }
+
+    /// The RNG seed this config gives a battle between all of `ids`, in any
+    /// order
+    pub fn seed_for_all(&self, ids: &[Uuid]) -> [u8; 32] {
+        match self.seed {
+            BattleSeed::Day(day) => day_seed(day, ids),
+            BattleSeed::Fixed(seed) => seed,
+        }
+    }
+}
+
+/// Fight today's battle between `a` and `b`. See [`battle_with`] to pick the day or seed.
+pub fn battle(a: &Monster, b: &Monster) -> BattleResult {
+    battle_with(a, b, &BattleConfig::today())
+}
+
+pub fn battle_with(a: &Monster, b: &Monster, config: &BattleConfig) -> BattleResult {
+    battle_with_strategies(a, b, config, [&mut AutoStrategy, &mut AutoStrategy])
+}
+
+/// [`battle_with`], with `strategies` deciding for `a` and `b` in that order
+pub fn battle_with_strategies(
+    a: &Monster,
+    b: &Monster,
+    config: &BattleConfig,
+    strategies: [&mut dyn BattleStrategy; 2],
+) -> BattleResult {
+    let mut state = BattleState::new(a, b, config);
+    state.play_out(strategies);
+    state.into_result()
+}
+
+impl BattleResult {
+    pub fn winner(&self) -> Option<Uuid> {
+        self.outcome.winner()
+    }
+}
+
+/// Swap fainted monsters for their next conscious teammate. False once a
+/// side has nobody left.
+fn send_in_replacements(sides: &mut [Side; 2], events: &mut Vec<BattleEvent>, turn: u32) -> bool {
+    if sides.iter().any(Side::defeated) {
+        return false;
+    }
+    for side in sides.iter_mut() {
+        if side.front().hp > 0 {
+            continue;
+        }
+        let next = side
+            .roster
+            .iter()
+            .position(|f| f.hp > 0)
+            .expect("side isn't defeated");
+        events.push(BattleEvent::SwitchIn {
+            turn,
+            monster: side.roster[next].mon.id,
+            replacing: side.front().mon.id,
+            forced: true,
+        });
+        side.active = next;
+    }
+    true
+}
+
+/// Timeline ticks in a turn
+const TURN_TIME: u32 = 100;
+
+/// Timeline ticks between a monster's actions: one turn at 20 SPD, less
+/// the faster it is
+fn action_delay(mon: &Monster) -> u32 {
+    6000 / (mon.stats.spd.max(0) as u32 + 40)
+}
+
+/// Work out how a finished battle ended from the HP both sides have left
+fn decide(a: &Monster, b: &Monster, a_hp: i16, b_hp: i16, tie_break: TieBreak) -> BattleOutcome {
+    if a_hp <= 0 || b_hp <= 0 {
+        let winner = if a_hp > b_hp { a.id } else { b.id };
+        return BattleOutcome::Knockout { winner };
+    }
+    if a_hp != b_hp {
+        let winner = if a_hp > b_hp { a.id } else { b.id };
+        return BattleOutcome::Points { winner };
+    }
+    let (a_key, b_key) = ((a.stats.spd, a.stats.atk), (b.stats.spd, b.stats.atk));
+    match tie_break {
+        TieBreak::Stats if a_key != b_key => BattleOutcome::Tiebreak {
+            winner: if a_key > b_key { a.id } else { b.id },
+        },
+        _ => BattleOutcome::Draw,
+    }
+}
+
+/// One team during a battle, with the member currently out front
+#[derive(Debug, Clone)]
+struct Side {
+    roster: Vec<Fighter>,
+    active: usize,
+}
+
+impl Side {
+    fn new(team: &[Monster], rules: &BattleRules) -> Self {
+        Side {
+            roster: team.iter().map(|m| Fighter::new(m, rules)).collect(),
+            active: 0,
+        }
+    }
+
+    fn front(&self) -> &Fighter {
+        &self.roster[self.active]
+    }
+
+    fn front_mut(&mut self) -> &mut Fighter {
+        &mut self.roster[self.active]
+    }
+
+    fn defeated(&self) -> bool {
+        self.roster.iter().all(|f| f.hp <= 0)
+    }
+}
+
+/// One monster's state during a battle
+#[derive(Debug, Clone)]
+struct Fighter {
+    mon: Monster,
+    hp: i16,
+    /// `None` when the rules have monsters only attack
+    moves: Option<MoveSet>,
+    /// DEF bonus from a guard move, until this monster's next move
+    guard: i16,
+    /// Uses left of each move, indexed by [`MoveKind`]; `None` is unlimited
+    uses: [Option<u8>; 4],
+    statuses: Vec<Status>,
+    /// Already used its lucky escape
+    escaped: bool,
+}
+
+impl Fighter {
+    fn new(mon: &Monster, rules: &BattleRules) -> Self {
+        let moves = rules.moves.then(|| mon.move_set());
+        let uses = match &moves {
+            Some(set) => MoveKind::ALL.map(|k| set.get(k).uses),
+            None => [None; 4],
+        };
+        Fighter {
+            mon: mon.clone(),
+            hp: mon.stats.hp,
+            moves,
+            guard: 0,
+            uses,
+            statuses: Vec::new(),
+            escaped: false,
+        }
+    }
+
+    fn can_use(&self, kind: MoveKind) -> bool {
+        self.uses[kind as usize] != Some(0)
+    }
+
+    fn view(&self) -> FighterView<'_> {
+        FighterView {
+            monster: &self.mon,
+            hp: self.hp,
+            guard: self.guard,
+            moves: self.moves.as_ref(),
+            uses: self.uses,
+            statuses: self
+                .statuses
+                .iter()
+                .map(|s| (s.kind, s.moves_left))
+                .collect(),
+        }
+    }
+
+    fn has(&self, kind: StatusKind) -> bool {
+        self.statuses.iter().any(|s| s.kind == kind)
+    }
+
+    fn hit_penalty(&self) -> i16 {
+        self.statuses.iter().map(|s| s.kind.hit_penalty()).sum()
+    }
+
+    fn block_penalty(&self) -> i16 {
+        self.statuses.iter().map(|s| s.kind.block_penalty()).sum()
+    }
+
+    /// Burn damage and stuns at the start of a move. False if the monster
+    /// fainted or lost the move.
+    fn start_move(&mut self, events: &mut Vec<BattleEvent>, turn: u32) -> bool {
+        let id = self.mon.id;
+        if self.has(StatusKind::Burn) {
+            let hp_before = self.hp;
+            self.hp -= BURN_DAMAGE;
+            events.push(BattleEvent::StatusTick {
+                turn,
+                monster: id,
+                status: StatusKind::Burn,
+                amount: BURN_DAMAGE,
+                hp_before: hp_before.max(0),
+                hp_after: self.hp.max(0),
+            });
+            if self.hp <= 0 {
+                events.push(BattleEvent::Faint { turn, monster: id });
+                return false;
+            }
+        }
+        if self.has(StatusKind::Stun) {
+            events.push(BattleEvent::Stunned { turn, monster: id });
+            return false;
+        }
+        true
+    }
+
+    /// Count down statuses once the move is over
+    fn end_move(&mut self, events: &mut Vec<BattleEvent>, turn: u32) {
+        if self.hp <= 0 {
+            return;
+        }
+        for status in &mut self.statuses {
+            status.moves_left -= 1;
+            if status.moves_left == 0 {
+                events.push(BattleEvent::StatusEnded {
+                    turn,
+                    monster: self.mon.id,
+                    status: status.kind,
+                });
+            }
+        }
+        self.statuses.retain(|s| s.moves_left > 0);
+    }
+}
+
+/// The strategies' RNG: the battle seed on another ChaCha stream, so the
+/// battle itself only depends on the actions taken
+fn strategy_rng(seed: [u8; 32]) -> ChaCha8Rng {
+    let mut rng = ChaCha8Rng::from_seed(seed);
+    rng.set_stream(1);
+    rng
+}
+
+/// Ask `strategy` for `me`'s action. It isn't asked when attacking is the
+/// only option, and anything not legal counts as an attack.
+fn choose_action(
+    rng: &mut ChaCha8Rng,
+    rules: &BattleRules,
+    strategy: &mut dyn BattleStrategy,
+    me: &Fighter,
+    bench: Vec<(usize, &Fighter)>,
+    foe: &Fighter,
+    turn: u32,
+) -> Action {
+    let attack = Action::Move(MoveKind::Attack);
+    let legal = legal_actions(me, &bench);
+    if legal == [attack] {
+        return attack;
+    }
+
+    let view = BattleView {
+        turn,
+        rules,
+        me: me.view(),
+        foe: foe.view(),
+        bench: bench.into_iter().map(|(i, f)| (i, f.view())).collect(),
+        legal,
+    };
+    let action = strategy.choose(&view, rng);
+    if view.legal.contains(&action) {
+        action
+    } else {
+        attack
+    }
+}
+
+/// Moves `me` has uses left of, or just attacking with moves off, then
+/// switches to each of `bench`
+fn legal_actions(me: &Fighter, bench: &[(usize, &Fighter)]) -> Vec<Action> {
+    let mut legal = match &me.moves {
+        Some(_) => MoveKind::ALL
+            .into_iter()
+            .filter(|&k| me.can_use(k))
+            .map(Action::Move)
+            .collect(),
+        None => vec![Action::Move(MoveKind::Attack)],
+    };
+    legal.extend(bench.iter().map(|&(i, _)| Action::Switch(i)));
+    legal
+}
+
+/// Carry out move `kind`; with moves off it's always a plain attack
+fn take_move(
+    rng: &mut ChaCha8Rng,
+    rules: &BattleRules,
+    me: &mut Fighter,
+    foe: &mut Fighter,
+    kind: MoveKind,
+    events: &mut Vec<BattleEvent>,
+    turn: u32,
+) {
+    let Some(moves) = &me.moves else {
+        attack(rng, rules, me, foe, None, events, turn);
+        return;
+    };
+    let mv = moves.get(kind).clone();
+    if let Some(left) = &mut me.uses[kind as usize] {
+        *left -= 1;
+    }
+    events.push(BattleEvent::MoveUsed {
+        turn,
+        monster: me.mon.id,
+        kind,
+        name: mv.name.clone(),
+    });
+
+    match kind {
+        MoveKind::Attack | MoveKind::Special => {
+            attack(rng, rules, me, foe, Some(&mv), events, turn)
+        }
+        MoveKind::Guard => {
+            me.guard = mv.power;
+            events.push(BattleEvent::Guard {
+                turn,
+                monster: me.mon.id,
+                bonus: mv.power,
+            });
+        }
+        MoveKind::Heal => {
+            let hp_before = me.hp;
+            me.hp = (me.hp + mv.power).min(me.mon.stats.hp);
+            events.push(BattleEvent::Heal {
+                turn,
+                monster: me.mon.id,
+                amount: me.hp - hp_before,
+                hp_before,
+                hp_after: me.hp,
+            });
+        }
+    }
+}
+
+/// Roll an attack from `atk` on `def`, using `mv` if the rules have moves
+fn attack(
+    rng: &mut ChaCha8Rng,
+    rules: &BattleRules,
+    atk: &Fighter,
+    def: &mut Fighter,
+    mv: Option<&Move>,
+    events: &mut Vec<BattleEvent>,
+    turn: u32,
+) {
+    let (atk_mon, def_mon) = (&atk.mon, &def.mon);
+    let accuracy = rules
+        .evasion
+        .then(|| roll_accuracy(rng, atk_mon, def_mon, events, turn));
+    if accuracy == Some(HitResult::Miss) {
+        return;
+    }
+    let graze = accuracy == Some(HitResult::Graze);
+    let power = mv.map_or(0, |m| m.power);
+    let effect = rules.type_chart.effect(atk_mon.element, def_mon.element);
+    let adv = if effect == Effect::Super {
+        rules.advantage_bonus
+    } else {
+        0
+    };
+    let luck_divisor = rules.luck_divisor.max(1);
+    let hit_roll = roll(rng, rules);
+    // saturating, so house rules can't wrap a roll round to negative
+    let hit = hit_roll
+        .saturating_add(atk_mon.stats.atk)
+        .saturating_add(atk_mon.stats.luck / luck_divisor)
+        .saturating_add(adv)
+        .saturating_add(power)
+        .saturating_sub(atk.hit_penalty());
+    let block_roll = roll(rng, rules);
+    let block = block_roll
+        .saturating_add(def_mon.stats.def)
+        .saturating_add(def_mon.stats.luck / luck_divisor)
+        .saturating_add(def.guard)
+        .saturating_sub(def.block_penalty());
+    events.push(BattleEvent::Attack {
+        turn,
+        attacker: atk_mon.id,
+        defender: def_mon.id,
+        hit_roll,
+        hit,
+        block_roll,
+        block,
+    });
+    if adv != 0 {
+        events.push(BattleEvent::ElementAdvantage {
+            turn,
+            attacker: atk_mon.id,
+            defender: def_mon.id,
+            bonus: adv,
+        });
+    }
+
+    let mut dmg = hit.saturating_sub(block).max(rules.min_damage);
+    let percent = rules
+        .type_chart
+        .multiplier(atk_mon.element, def_mon.element);
+    if percent != 100 {
+        let scaled = i32::from(dmg) * i32::from(percent) / 100;
+        dmg = (scaled.min(i32::from(i16::MAX)) as i16).max(rules.min_damage);
+        events.push(BattleEvent::Effectiveness {
+            turn,
+            attacker: atk_mon.id,
+            defender: def_mon.id,
+            effect,
+            percent,
+        });
+    }
+    let mut is_crit = false;
+    if graze {
+        dmg = (dmg / 2).max(rules.min_damage);
+    } else {
+        let crit_chance = if rules.evasion {
+            atk_mon.stats.crit + atk_mon.stats.luck / 2
+        } else {
+            atk_mon.stats.crit
+        };
+        let crit_roll: i16 = rng.gen_range(0..100);
+        is_crit = crit_roll < crit_chance;
+
+        if is_crit {
+            dmg = dmg.saturating_mul(rules.crit_multiplier);
+            events.push(BattleEvent::Crit {
+                turn,
+                attacker: atk_mon.id,
+                defender: def_mon.id,
+                roll: crit_roll,
+                chance: crit_chance,
+            });
+        }
+    }
+
+    if rules.evasion && !def.escaped && def.hp > 1 && dmg >= def.hp {
+        let chance = def_mon.stats.luck * LUCKY_ESCAPE_PER_LUCK;
+        let roll: i16 = rng.gen_range(0..100);
+        if roll < chance {
+            def.escaped = true;
+            dmg = def.hp - 1;
+            events.push(BattleEvent::LuckyEscape {
+                turn,
+                monster: def_mon.id,
+                roll,
+                chance,
+            });
+        }
+    }
+
+    let hp_before = def.hp;
+    def.hp -= dmg;
+
+    events.push(BattleEvent::Damage {
+        turn,
+        attacker: atk_mon.id,
+        defender: def_mon.id,
+        amount: dmg,
+        crit: is_crit,
+        hp_before: hp_before.max(0),
+        hp_after: def.hp.max(0),
+    });
+    if def.hp <= 0 {
+        events.push(BattleEvent::Faint {
+            turn,
+            monster: def_mon.id,
+        });
+        return;
+    }
+
+    if graze {
+        return;
+    }
+    let special = mv.is_some_and(|m| m.kind == MoveKind::Special);
+    inflict_status(rng, rules, atk_mon, def, special, events, turn);
+}
+
+/// Base percent chance to land a clean hit, before SPD and LUCK
+const BASE_ACCURACY: i16 = 80;
+/// Rolls just past the hit chance graze instead of missing
+const GRAZE_BAND: i16 = 15;
+/// Percent chance per point of LUCK to survive a knockout at 1 HP, once a
+/// battle
+const LUCKY_ESCAPE_PER_LUCK: i16 = 2;
+
+/// Roll whether an attack hits, grazes or misses. Faster, luckier attackers
+/// land more; faster, luckier defenders dodge more.
+fn roll_accuracy(
+    rng: &mut ChaCha8Rng,
+    atk: &Monster,
+    def: &Monster,
+    events: &mut Vec<BattleEvent>,
+    turn: u32,
+) -> HitResult {
+    let spd_edge = ((atk.stats.spd - def.stats.spd) / 4).clamp(-20, 20);
+    let chance = (BASE_ACCURACY + spd_edge + atk.stats.luck - def.stats.luck).clamp(40, 100);
+    let roll: i16 = rng.gen_range(0..100);
+    let result = if roll < chance {
+        HitResult::Hit
+    } else if roll < chance + GRAZE_BAND {
+        HitResult::Graze
+    } else {
+        HitResult::Miss
+    };
+    events.push(BattleEvent::Accuracy {
+        turn,
+        attacker: atk.id,
+        defender: def.id,
+        roll,
+        chance,
+        result,
+    });
+    result
+}
+
+/// Maybe leave the attacker's element status on `def` after a hit
+fn inflict_status(
+    rng: &mut ChaCha8Rng,
+    rules: &BattleRules,
+    atk: &Monster,
+    def: &mut Fighter,
+    special: bool,
+    events: &mut Vec<BattleEvent>,
+    turn: u32,
+) {
+    let Some(status) = StatusKind::for_element(atk.element) else {
+        return;
+    };
+    if rules.status_chance <= 0 {
+        return;
+    }
+    let chance = if special {
+        rules.status_chance.saturating_mul(2)
+    } else {
+        rules.status_chance
+    };
+    if rng.gen_range(0..100) >= chance {
+        return;
+    }
+
+    let monster = def.mon.id;
+    let resist = def.mon.stats.luck.saturating_mul(rules.resist_per_luck);
+    let roll: i16 = rng.gen_range(0..100);
+    if roll < resist {
+        events.push(BattleEvent::StatusResisted {
+            turn,
+            monster,
+            status,
+            roll,
+            chance: resist,
+        });
+        return;
+    }
+
+    let moves = status.duration();
+    def.statuses.retain(|s| s.kind != status);
+    def.statuses.push(Status {
+        kind: status,
+        moves_left: moves,
+    });
+    if status == StatusKind::Knockback {
+        def.guard = 0;
+    }
+    events.push(BattleEvent::StatusApplied {
+        turn,
+        monster,
+        status,
+        moves,
+    });
+}
+
+/// One attack or block die; fewer than one side counts as one
+fn roll(rng: &mut ChaCha8Rng, rules: &BattleRules) -> i16 {
+    rng.gen_range(1..=rules.die_sides.max(1))
+}
+
+/// Generate a battle seed from a day number and every participant's ID.
+/// This makes battles deterministic for the same day but different across days.
+fn day_seed(day: u32, ids: &[Uuid]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&day.to_le_bytes());
+
+    // Sort IDs so battle(a,b) == battle(b,a) for same-day consistency
+    let mut ids = ids.to_vec();
+    ids.sort();
+    for id in &ids {
+        hasher.update(id.as_bytes());
+    }
+
+    *hasher.finalize().as_bytes()
+}
+
+/// Get the current day as days since Unix epoch.
+/// Works on both wasm and native targets.
+pub fn current_day() -> u32 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        // In wasm, use js_sys to get current time
+        let millis = js_sys::Date::now();
+        let secs = (millis / 1000.0) as u64;
+        (secs / 86400) as u32
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        (secs / 86400) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_monster, Element};
+
+    #[test]
+    fn test_battle_is_reproducible() {
+        let a = generate_monster([1u8; 32]);
+        let b = generate_monster([2u8; 32]);
+
+        let day = BattleConfig::on_day(20_000);
+        let first = battle_with(&a, &b, &day);
+        let again = battle_with(&a, &b, &day);
+        assert_eq!(first.outcome, again.outcome);
+        assert_eq!(first.turns, again.turns);
+        assert_eq!(first.seed, battle_with(&b, &a, &day).seed);
+
+        let replay = battle_with(&a, &b, &BattleConfig::with_seed(first.seed));
+        assert_eq!(replay.outcome, first.outcome);
+        assert_eq!(replay.a_remaining_hp, first.a_remaining_hp);
+        assert_eq!(replay.b_remaining_hp, first.b_remaining_hp);
+
+        assert_ne!(
+            first.seed,
+            battle_with(&a, &b, &BattleConfig::on_day(20_001)).seed
+        );
+    }
+
+    #[test]
+    fn test_events_match_log() {
+        let a = generate_monster([3u8; 32]);
+        let b = generate_monster([4u8; 32]);
+        let res = battle_with(&a, &b, &BattleConfig::on_day(20_000));
+
+        let damage: Vec<_> = res
+            .events
+            .iter()
+            .filter_map(|e| match e {
+                BattleEvent::Damage {
+                    defender, hp_after, ..
+                } => Some((*defender, *hp_after)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(damage.len(), res.log.len());
+        assert!(res.log[0].0.starts_with("T1: "));
+
+        let last_hp = |id| {
+            damage
+                .iter()
+                .rev()
+                .find(|(d, _)| *d == id)
+                .map(|(_, hp)| *hp)
+        };
+        assert_eq!(last_hp(a.id).unwrap_or(a.stats.hp), res.a_remaining_hp);
+        assert_eq!(last_hp(b.id).unwrap_or(b.stats.hp), res.b_remaining_hp);
+        assert_eq!(
+            res.events.last(),
+            Some(&BattleEvent::Outcome {
+                outcome: res.outcome,
+                turns: res.turns
+            })
+        );
+    }
+
+    #[test]
+    fn test_stalemate_outcomes() {
+        // two walls that can only chip each other for 1 a hit
+        let mut a = generate_monster([5u8; 32]);
+        a.stats.hp = 200;
+        a.stats.atk = 0;
+        a.stats.def = 100;
+        a.stats.crit = 0;
+        let mut b = a.clone();
+        b.id = Uuid::from_u128(1);
+
+        let mut config = BattleConfig::on_day(20_000);
+        let res = battle_with(&a, &b, &config);
+        assert_eq!(res.turns, config.rules.turn_cap);
+        assert_eq!(res.a_remaining_hp, res.b_remaining_hp);
+        assert_eq!(res.outcome, BattleOutcome::Draw);
+
+        b.stats.spd += 1;
+        assert_eq!(
+            battle_with(&a, &b, &config).outcome,
+            BattleOutcome::Tiebreak { winner: b.id }
+        );
+        config.rules.tie_break = TieBreak::Draw;
+        assert_eq!(battle_with(&a, &b, &config).outcome, BattleOutcome::Draw);
+
+        b.stats.hp += 1;
+        assert_eq!(
+            battle_with(&a, &b, &config).outcome,
+            BattleOutcome::Points { winner: b.id }
+        );
+    }
+
+    #[test]
+    fn test_house_rules() {
+        let a = generate_monster([6u8; 32]);
+        let b = generate_monster([7u8; 32]);
+
+        let rules: BattleRules =
+            serde_json::from_str(r#"{"turn_cap": 1, "min_damage": 0}"#).unwrap();
+        assert_eq!(rules.die_sides, BattleRules::classic().die_sides);
+        assert_ne!(rules.hash(), BattleRules::classic().hash());
+        assert_eq!(rules.validate(), Ok(()));
+
+        let classic = BattleConfig::on_day(20_000);
+        let house = BattleConfig {
+            rules,
+            ..classic.clone()
+        };
+        let res = battle_with(&a, &b, &house);
+        assert_eq!(res.turns, 1);
+        assert_eq!(res.rules_hash, house.rules.hash());
+        assert_eq!(
+            battle_with(&a, &b, &classic).rules_hash,
+            BattleRules::classic().hash()
+        );
+    }
+
+    #[test]
+    fn test_rules_validate() {
+        assert_eq!(BattleRules::classic().validate(), Ok(()));
+        assert_eq!(BattleRules::standard().validate(), Ok(()));
+
+        let rules = BattleRules {
+            crit_multiplier: i16::MAX,
+            ..BattleRules::classic()
+        };
+        assert_eq!(
+            rules.validate(),
+            Err(RulesError {
+                rule: "crit_multiplier",
+                value: i16::MAX.into(),
+                allowed: 1..=5,
+            })
+        );
+        let rules = BattleRules {
+            turn_cap: 0,
+            ..BattleRules::classic()
+        };
+        assert_eq!(rules.validate().unwrap_err().rule, "turn_cap");
+        let rules = BattleRules {
+            die_sides: -1,
+            ..BattleRules::classic()
+        };
+        assert_eq!(rules.validate().unwrap_err().rule, "die_sides");
+    }
+
+    #[test]
+    fn test_unchecked_rules_saturate() {
+        // rules that fail validation still mustn't overflow the engine
+        let config = BattleConfig {
+            rules: BattleRules {
+                die_sides: i16::MAX,
+                advantage_bonus: i16::MAX,
+                crit_multiplier: i16::MAX,
+                status_chance: i16::MAX,
+                resist_per_luck: i16::MAX,
+                ..BattleRules::standard()
+            },
+            ..BattleConfig::on_day(20_000)
+        };
+        let mut crits = 0;
+        for seed in 0..40u8 {
+            let a = generate_monster([seed; 32]);
+            let b = generate_monster([seed + 100; 32]);
+            for event in battle_with(&a, &b, &config).events {
+                match event {
+                    BattleEvent::Crit { .. } => crits += 1,
+                    BattleEvent::Damage { amount, .. } => assert!(amount >= 0),
+                    _ => {}
+                }
+            }
+        }
+        assert!(crits > 0);
+    }
+
+    #[test]
+    fn test_move_sets_in_battle() {
+        let a = generate_monster([17u8; 32]);
+        let b = generate_monster([18u8; 32]);
+        let mut config = BattleConfig::on_day(20_000);
+        let classic = battle_with(&a, &b, &config);
+        assert!(!classic
+            .events
+            .iter()
+            .any(|e| matches!(e, BattleEvent::MoveUsed { .. })));
+
+        config.rules = BattleRules {
+            moves: true,
+            ..BattleRules::classic()
+        };
+        let res = battle_with(&a, &b, &config);
+        let mut used = Vec::new();
+        let mut last_user = None;
+        for event in &res.events {
+            match event {
+                BattleEvent::Attack { attacker, .. } => {
+                    assert_eq!(last_user, Some(*attacker));
+                }
+                BattleEvent::MoveUsed {
+                    monster,
+                    kind,
+                    name,
+                    ..
+                } => {
+                    let mon = if *monster == a.id { &a } else { &b };
+                    assert_eq!(name, &mon.move_set().get(*kind).name);
+                    used.push(*kind);
+                    last_user = Some(*monster);
+                }
+                BattleEvent::Heal {
+                    monster, hp_after, ..
+                } => {
+                    let mon = if *monster == a.id { &a } else { &b };
+                    assert!(*hp_after <= mon.stats.hp);
+                }
+                _ => {}
+            }
+        }
+        assert!(used.contains(&MoveKind::Attack));
+        assert!(res.log.iter().any(|l| l.0.contains(" uses ")));
+    }
+
+    #[test]
+    fn test_status_effects() {
+        let mut a = generate_monster([11u8; 32]);
+        let mut b = generate_monster([12u8; 32]);
+        a.element = Element::Fire;
+        b.element = Element::Electric;
+        for mon in [&mut a, &mut b] {
+            mon.stats.hp = 60;
+            mon.stats.crit = 0;
+            mon.stats.luck = 0;
+        }
+        let mut config = BattleConfig::on_day(20_000);
+        config.rules.status_chance = 100;
+
+        let res = battle_with(&a, &b, &config);
+        let applied = |id, status| {
+            res.events.iter().any(|e| {
+                matches!(e, BattleEvent::StatusApplied { monster, status: s, .. }
+                    if *monster == id && *s == status)
+            })
+        };
+        assert!(applied(b.id, StatusKind::Burn));
+        assert!(applied(a.id, StatusKind::Stun));
+        assert!(res
+            .events
+            .iter()
+            .any(|e| matches!(e, BattleEvent::StatusTick { monster, .. } if *monster == b.id)));
+        assert!(res
+            .events
+            .iter()
+            .any(|e| matches!(e, BattleEvent::Stunned { monster, .. } if *monster == a.id)));
+        assert!(res.log.iter().any(|l| l.0.contains("is burned")));
+        assert!(res.log.iter().any(|l| l.0.contains("burned damage")));
+
+        // max LUCK with a resist chance over 100% shrugs everything off
+        b.stats.luck = 20;
+        config.rules.resist_per_luck = 5;
+        let res = battle_with(&a, &b, &config);
+        assert!(!res
+            .events
+            .iter()
+            .any(|e| matches!(e, BattleEvent::StatusApplied { monster, .. } if *monster == b.id)));
+        assert!(res
+            .events
+            .iter()
+            .any(|e| matches!(e, BattleEvent::StatusResisted { .. })));
+    }
+
+    #[test]
+    fn test_type_chart_scales_damage() {
+        let mut a = generate_monster([13u8; 32]);
+        let mut b = generate_monster([14u8; 32]);
+        a.element = Element::Air;
+        b.element = Element::Fire;
+        a.stats.hp = 60;
+        b.stats.hp = 60;
+        let mut config = BattleConfig::on_day(20_000);
+        config.rules.type_chart = TypeChart::balanced();
+
+        let res = battle_with(&a, &b, &config);
+        let percents: Vec<_> = res
+            .events
+            .iter()
+            .filter_map(|e| match e {
+                BattleEvent::Effectiveness {
+                    attacker, percent, ..
+                } => Some((*attacker == a.id, *percent)),
+                _ => None,
+            })
+            .collect();
+        assert!(percents.contains(&(true, 150)));
+        assert!(percents.contains(&(false, 75)));
+        assert!(res.log.iter().any(|l| l.0.contains("super effective")));
+    }
+
+    #[test]
+    fn test_timeline_initiative() {
+        let mut fast = generate_monster([15u8; 32]);
+        let mut slow = generate_monster([16u8; 32]);
+        for mon in [&mut fast, &mut slow] {
+            mon.stats.hp = 200;
+            mon.stats.crit = 0;
+        }
+        fast.stats.spd = 60;
+        slow.stats.spd = 7;
+        let mut config = BattleConfig::on_day(20_000);
+        config.rules.initiative = Initiative::Timeline;
+        config.rules.turn_cap = 10;
+
+        let res = battle_with(&slow, &fast, &config);
+        let order: Vec<_> = res
+            .events
+            .iter()
+            .filter_map(|e| match e {
+                BattleEvent::Initiative { monster, time, .. } => Some((*monster, *time)),
+                _ => None,
+            })
+            .collect();
+        assert!(order.windows(2).all(|w| w[0].1 <= w[1].1));
+        let count = |id| order.iter().filter(|(m, _)| *m == id).count();
+        assert_eq!(count(fast.id), 10 * 100 / action_delay(&fast) as usize);
+        assert!(count(fast.id) >= 2 * count(slow.id));
+
+        // same SPD means every slot is a tie, settled by the battle RNG
+        slow.stats.spd = 60;
+        let res = battle_with(&slow, &fast, &config);
+        assert!(res
+            .events
+            .iter()
+            .any(|e| matches!(e, BattleEvent::Initiative { tie: true, .. })));
+        assert_eq!(
+            res.events,
+            battle_with(&slow, &fast, &config).events,
+            "ties are deterministic"
+        );
+    }
+
+    #[test]
+    fn test_evasion_and_luck() {
+        let mut a = generate_monster([17u8; 32]);
+        let mut b = generate_monster([18u8; 32]);
+        for mon in [&mut a, &mut b] {
+            mon.stats.hp = 200;
+            mon.stats.crit = 0;
+        }
+        a.stats.spd = 0;
+        a.stats.luck = 0;
+        b.stats.spd = 200;
+        b.stats.luck = 20;
+        let mut config = BattleConfig::on_day(20_000);
+        config.rules.evasion = true;
+
+        let res = battle_with(&a, &b, &config);
+        let results: Vec<_> = res
+            .events
+            .iter()
+            .filter_map(|e| match e {
+                BattleEvent::Accuracy {
+                    attacker,
+                    chance,
+                    result,
+                    ..
+                } if *attacker == a.id => Some((*chance, *result)),
+                _ => None,
+            })
+            .collect();
+        assert!(results.iter().all(|(chance, _)| *chance == 40));
+        assert!(results.iter().any(|(_, r)| *r == HitResult::Miss));
+        assert!(results.iter().any(|(_, r)| *r == HitResult::Graze));
+        assert!(res.log.iter().any(|l| l.0.contains(" misses ")));
+
+        // a one-shot blow that 20 LUCK sometimes survives, but only once
+        a.stats.atk = 500;
+        b.stats.hp = 10;
+        b.stats.spd = 0;
+        b.stats.luck = 0;
+        let mut escapes = 0;
+        for day in 20_000..20_050 {
+            config.seed = BattleSeed::Day(day);
+            let res = battle_with(&a, &b, &config);
+            assert!(!res
+                .events
+                .iter()
+                .any(|e| matches!(e, BattleEvent::LuckyEscape { .. })));
+
+            b.stats.luck = 20;
+            let res = battle_with(&a, &b, &config);
+            b.stats.luck = 0;
+            let count = res
+                .events
+                .iter()
+                .filter(|e| matches!(e, BattleEvent::LuckyEscape { .. }))
+                .count();
+            assert!(count <= 1);
+            escapes += count;
+        }
+        assert!(escapes > 0);
+    }
+}
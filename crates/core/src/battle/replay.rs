@@ -0,0 +1,225 @@
+//! Self-contained battle records: everything needed to fight a battle again
+//! and check it comes out the same.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Action, BattleConfig, BattleError, BattleEvent, BattleResult, BattleRules, BattleState,
+    RulesError,
+};
+use crate::{Monster, ValidateError};
+
+/// Layout of [`BattleReplay`] this build writes and verifies
+pub const REPLAY_VERSION: u8 = 1;
+
+/// A one-on-one battle as it was fought. Doesn't depend on the day or on
+/// either player's collection, so it re-runs the same anywhere.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BattleReplay {
+    /// [`REPLAY_VERSION`] when recorded
+    pub version: u8,
+    pub a: Monster,
+    pub b: Monster,
+    pub rules: BattleRules,
+    /// [`BattleRules::hash`] of `rules` when recorded
+    pub rules_hash: [u8; 32],
+    pub seed: [u8; 32],
+    /// Every action taken, in order
+    pub actions: Vec<Action>,
+    /// [`events_hash`] of everything that happened, outcome included
+    pub hash: [u8; 32],
+}
+
+impl BattleReplay {
+    /// Record `result`, a battle `a` and `b` fought under `rules`
+    pub fn new(a: &Monster, b: &Monster, rules: &BattleRules, result: &BattleResult) -> Self {
+        BattleReplay {
+            version: REPLAY_VERSION,
+            a: a.clone(),
+            b: b.clone(),
+            rules: rules.clone(),
+            rules_hash: result.rules_hash,
+            seed: result.seed,
+            actions: result.actions.clone(),
+            hash: events_hash(&result.events),
+        }
+    }
+}
+
+/// Why a replay failed [`verify_replay`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    /// Recorded by a build with a layout we don't know
+    UnsupportedVersion(u8),
+    /// The rules don't match the hash recorded with them
+    RulesMismatch,
+    /// The rules are outside what [`BattleRules::validate`] allows
+    InvalidRules(RulesError),
+    /// One of the monsters couldn't have come from a share code
+    Invalid(ValidateError),
+    /// Action `index` wasn't allowed at that point in the battle
+    IllegalAction { index: usize, action: Action },
+    /// The battle was over before action `index`
+    ExtraActions { index: usize },
+    /// The actions ran out before the battle was over
+    Unfinished,
+    /// The battle re-ran differently from how it was recorded
+    HashMismatch,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::UnsupportedVersion(v) => write!(f, "unsupported replay version {v}"),
+            ReplayError::RulesMismatch => write!(f, "rules don't match their hash"),
+            ReplayError::InvalidRules(e) => write!(f, "invalid rules: {e}"),
+            ReplayError::Invalid(e) => write!(f, "invalid monster: {e}"),
+            ReplayError::IllegalAction { index, action } => {
+                write!(f, "action {index} ({action:?}) wasn't allowed")
+            }
+            ReplayError::ExtraActions { index } => {
+                write!(f, "battle was over before action {index}")
+            }
+            ReplayError::Unfinished => write!(f, "actions ran out before the battle ended"),
+            ReplayError::HashMismatch => write!(f, "battle doesn't match the recorded hash"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReplayError::Invalid(e) => Some(e),
+            ReplayError::InvalidRules(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Fight `replay` again and check every event matches. Returns the
+/// re-run battle.
+pub fn verify_replay(replay: &BattleReplay) -> Result<BattleResult, ReplayError> {
+    if replay.version != REPLAY_VERSION {
+        return Err(ReplayError::UnsupportedVersion(replay.version));
+    }
+    if replay.rules.hash() != replay.rules_hash {
+        return Err(ReplayError::RulesMismatch);
+    }
+    // whoever edits the rules can redo the hash too
+    replay.rules.validate().map_err(ReplayError::InvalidRules)?;
+    replay.a.validate().map_err(ReplayError::Invalid)?;
+    replay.b.validate().map_err(ReplayError::Invalid)?;
+
+    let config = BattleConfig {
+        rules: replay.rules.clone(),
+        ..BattleConfig::with_seed(replay.seed)
+    };
+    let mut state = BattleState::new(&replay.a, &replay.b, &config);
+    for (index, &action) in replay.actions.iter().enumerate() {
+        state.apply(action).map_err(|e| match e {
+            BattleError::Finished => ReplayError::ExtraActions { index },
+            BattleError::IllegalAction(action) => ReplayError::IllegalAction { index, action },
+        })?;
+    }
+    if !state.is_finished() {
+        return Err(ReplayError::Unfinished);
+    }
+
+    let result = state.into_result();
+    if events_hash(&result.events) != replay.hash {
+        return Err(ReplayError::HashMismatch);
+    }
+    Ok(result)
+}
+
+/// Fingerprint of a battle's events, to tell whether two runs match
+pub fn events_hash(events: &[BattleEvent]) -> [u8; 32] {
+    let bytes = bincode::serialize(events).expect("events always serialize");
+    blake3::derive_key("qrmonsters battle events v1", &bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{battle_with, generate_monster, MoveKind};
+
+    fn recorded() -> BattleReplay {
+        let a = generate_monster([15; 32]);
+        let b = generate_monster([20; 32]);
+        let config = BattleConfig {
+            rules: BattleRules::standard(),
+            ..BattleConfig::on_day(20_000)
+        };
+        let res = battle_with(&a, &b, &config);
+        BattleReplay::new(&a, &b, &config.rules, &res)
+    }
+
+    #[test]
+    fn test_replay_verifies() {
+        let replay = recorded();
+        let res = verify_replay(&replay).unwrap();
+        assert_eq!(res.actions, replay.actions);
+
+        // the day it was fought doesn't matter any more
+        let a = generate_monster([15; 32]);
+        let b = generate_monster([20; 32]);
+        let today = BattleConfig {
+            rules: BattleRules::standard(),
+            ..BattleConfig::on_day(20_001)
+        };
+        assert_ne!(battle_with(&a, &b, &today).seed, replay.seed);
+        assert_eq!(res.seed, replay.seed);
+    }
+
+    #[test]
+    fn test_replay_flags_tampering() {
+        let replay = recorded();
+
+        // a point moved from DEF to ATK still passes validation, so only
+        // the re-run can catch it
+        let mut stronger = replay.clone();
+        stronger.a.stats.def -= 1;
+        stronger.a.stats.atk += 1;
+        assert_eq!(stronger.a.validate(), Ok(()));
+        assert_eq!(
+            verify_replay(&stronger).err(),
+            Some(ReplayError::HashMismatch)
+        );
+
+        let mut rules = replay.clone();
+        rules.rules.crit_multiplier = 3;
+        assert_eq!(
+            verify_replay(&rules).err(),
+            Some(ReplayError::RulesMismatch)
+        );
+
+        let mut short = replay.clone();
+        short.actions.pop();
+        assert_eq!(verify_replay(&short).err(), Some(ReplayError::Unfinished));
+
+        let mut long = replay.clone();
+        long.actions.push(Action::Move(MoveKind::Attack));
+        assert_eq!(
+            verify_replay(&long).err(),
+            Some(ReplayError::ExtraActions {
+                index: replay.actions.len()
+            })
+        );
+
+        let mut illegal = replay.clone();
+        illegal.actions[0] = Action::Switch(1);
+        assert_eq!(
+            verify_replay(&illegal).err(),
+            Some(ReplayError::IllegalAction {
+                index: 0,
+                action: Action::Switch(1)
+            })
+        );
+
+        let mut hash = replay;
+        hash.hash[0] ^= 1;
+        assert_eq!(verify_replay(&hash).err(), Some(ReplayError::HashMismatch));
+    }
+}
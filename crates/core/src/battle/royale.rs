@@ -0,0 +1,251 @@
+//! Battle royale: everyone for themselves until one monster is left.
+
+use std::cmp::Reverse;
+
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{
+    choose_action, format_log, strategy_rng, take_move, Action, AutoStrategy, BattleConfig,
+    BattleEvent, BattleLine, Fighter,
+};
+use crate::{Monster, MoveKind};
+
+/// Where one monster finished
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Placement {
+    pub monster: Uuid,
+    /// 1 for the winner
+    pub place: u32,
+    /// Turn it was knocked out; `None` if it lasted to the end
+    pub eliminated_turn: Option<u32>,
+    /// Who landed the knockout; `None` for survivors and burn knockouts
+    pub eliminated_by: Option<Uuid>,
+    pub knockouts: u32,
+    pub remaining_hp: i16,
+}
+
+#[derive(Debug, Clone)]
+pub struct RoyaleResult {
+    /// Everyone, first place first
+    pub placements: Vec<Placement>,
+    pub turns: u32,
+    pub events: Vec<BattleEvent>,
+    pub log: Vec<BattleLine>,
+    pub seed: [u8; 32],
+    pub rules_hash: [u8; 32],
+}
+
+impl RoyaleResult {
+    /// First place, if anyone took part
+    pub fn winner(&self) -> Option<Uuid> {
+        self.placements.first().map(|p| p.monster)
+    }
+}
+
+/// Free-for-all between `monsters`. Each turn the living act in SPD order
+/// (the battle RNG settles ties) and attack a random opponent. Monsters are
+/// placed in reverse order of elimination; if the turn cap is reached, the
+/// survivors are ranked by HP left. The rules' initiative, first move and
+/// switching settings don't apply.
+pub fn royale_with(monsters: &[Monster], config: &BattleConfig) -> RoyaleResult {
+    let rules = &config.rules;
+    let ids: Vec<Uuid> = monsters.iter().map(|m| m.id).collect();
+    let seed = config.seed_for_all(&ids);
+    let mut rng = ChaCha8Rng::from_seed(seed);
+    // like one-on-one battles, strategies get their own stream so their
+    // choices can't shift the dice
+    let mut strategy_rng = strategy_rng(seed);
+
+    let mut fighters: Vec<Fighter> = monsters.iter().map(|m| Fighter::new(m, rules)).collect();
+    // like the seed, the fight mustn't depend on who signed up first
+    fighters.sort_by_key(|f| f.mon.id);
+    // (turn, knocked out by) for each eliminated monster
+    let mut out: Vec<Option<(u32, Option<Uuid>)>> = vec![None; fighters.len()];
+    let mut places: Vec<u32> = vec![0; fighters.len()];
+    let mut knockouts: Vec<u32> = vec![0; fighters.len()];
+    let alive = |fighters: &[Fighter]| fighters.iter().filter(|f| f.hp > 0).count();
+
+    let mut events = Vec::new();
+    let mut turn = 0;
+    while alive(&fighters) > 1 && turn < rules.turn_cap {
+        turn += 1;
+        events.push(BattleEvent::TurnStart { turn });
+
+        let mut order: Vec<usize> = (0..fighters.len())
+            .filter(|&i| fighters[i].hp > 0)
+            .collect();
+        order.shuffle(&mut rng);
+        order.sort_by_key(|&i| Reverse(fighters[i].mon.stats.spd));
+
+        for i in order {
+            if fighters[i].hp <= 0 {
+                continue;
+            }
+            let targets: Vec<usize> = (0..fighters.len())
+                .filter(|&t| t != i && fighters[t].hp > 0)
+                .collect();
+            let Some(&t) = targets.choose(&mut rng) else {
+                break;
+            };
+            events.push(BattleEvent::Target {
+                turn,
+                attacker: fighters[i].mon.id,
+                target: fighters[t].mon.id,
+            });
+
+            let (me, foe) = pair_mut(&mut fighters, i, t);
+            me.guard = 0;
+            if me.start_move(&mut events, turn) {
+                let kind = match choose_action(
+                    &mut strategy_rng,
+                    rules,
+                    &mut AutoStrategy,
+                    me,
+                    Vec::new(),
+                    foe,
+                    turn,
+                ) {
+                    Action::Move(kind) => kind,
+                    Action::Switch(_) => MoveKind::Attack,
+                };
+                take_move(&mut rng, rules, me, foe, kind, &mut events, turn);
+            }
+            me.end_move(&mut events, turn);
+
+            for (j, by) in [(i, None), (t, Some(fighters[i].mon.id))] {
+                if fighters[j].hp > 0 || out[j].is_some() {
+                    continue;
+                }
+                let place = alive(&fighters) as u32 + 1;
+                out[j] = Some((turn, by));
+                places[j] = place;
+                if by.is_some() {
+                    knockouts[i] += 1;
+                }
+                events.push(BattleEvent::Eliminated {
+                    turn,
+                    monster: fighters[j].mon.id,
+                    place,
+                });
+            }
+        }
+    }
+
+    // survivors take the top places, most HP first
+    let mut survivors: Vec<usize> = (0..fighters.len()).filter(|&i| out[i].is_none()).collect();
+    survivors.sort_by_key(|&i| Reverse(fighters[i].hp));
+    for (place, &i) in survivors.iter().enumerate() {
+        places[i] = place as u32 + 1;
+    }
+
+    let mut placements: Vec<Placement> = fighters
+        .iter()
+        .enumerate()
+        .map(|(i, f)| Placement {
+            monster: f.mon.id,
+            place: places[i],
+            eliminated_turn: out[i].map(|(turn, _)| turn),
+            eliminated_by: out[i].and_then(|(_, by)| by),
+            knockouts: knockouts[i],
+            remaining_hp: f.hp.max(0),
+        })
+        .collect();
+    placements.sort_by_key(|p| p.place);
+
+    events.push(BattleEvent::RoyaleOutcome {
+        ranking: placements.iter().map(|p| p.monster).collect(),
+        turns: turn,
+    });
+    let everyone: Vec<&Monster> = monsters.iter().collect();
+    let log = format_log(&events, &everyone);
+
+    RoyaleResult {
+        placements,
+        turns: turn,
+        events,
+        log,
+        seed,
+        rules_hash: rules.hash(),
+    }
+}
+
+/// Mutable borrows of two different elements
+fn pair_mut<T>(items: &mut [T], i: usize, j: usize) -> (&mut T, &mut T) {
+    assert_ne!(i, j);
+    if i < j {
+        let (left, right) = items.split_at_mut(j);
+        (&mut left[i], &mut right[0])
+    } else {
+        let (left, right) = items.split_at_mut(i);
+        (&mut right[0], &mut left[j])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_monster, BattleRules};
+
+    #[test]
+    fn test_royale_ranks_everyone() {
+        let monsters: Vec<_> = (0..6u8).map(|i| generate_monster([70 + i; 32])).collect();
+        let config = BattleConfig::on_day(20_000);
+        let res = royale_with(&monsters, &config);
+
+        let mut places: Vec<_> = res.placements.iter().map(|p| p.place).collect();
+        places.sort();
+        assert_eq!(places, (1..=6).collect::<Vec<_>>());
+        assert_eq!(res.winner(), Some(res.placements[0].monster));
+        let eliminated = res
+            .events
+            .iter()
+            .filter(|e| matches!(e, BattleEvent::Eliminated { .. }))
+            .count();
+        assert_eq!(
+            eliminated,
+            res.placements
+                .iter()
+                .filter(|p| p.eliminated_turn.is_some())
+                .count()
+        );
+        let knockouts: u32 = res.placements.iter().map(|p| p.knockouts).sum();
+        let credited = res
+            .placements
+            .iter()
+            .filter(|p| p.eliminated_by.is_some())
+            .count();
+        assert_eq!(knockouts as usize, credited);
+
+        // same fight whatever order everyone signed up in
+        let mut reversed = monsters.clone();
+        reversed.reverse();
+        let flipped = royale_with(&reversed, &config);
+        assert_eq!(flipped.seed, res.seed);
+        assert_eq!(flipped.placements, res.placements);
+        assert_eq!(flipped.events, res.events);
+        let again = royale_with(&monsters, &config);
+        assert_eq!(again.events, res.events);
+
+        // the two-monster seed is the one-on-one seed
+        assert_eq!(
+            royale_with(&monsters[..2], &config).seed,
+            config.seed_for(monsters[0].id, monsters[1].id)
+        );
+    }
+
+    #[test]
+    fn test_royale_with_standard_rules() {
+        let monsters: Vec<_> = (0..4u8).map(|i| generate_monster([80 + i; 32])).collect();
+        let config = BattleConfig {
+            rules: BattleRules::standard(),
+            ..BattleConfig::on_day(20_000)
+        };
+        let res = royale_with(&monsters, &config);
+        assert_eq!(res.placements.len(), 4);
+        assert!(res.log.iter().any(|l| l.0.contains(" goes after ")));
+    }
+}
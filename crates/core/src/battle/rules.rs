@@ -0,0 +1,200 @@
+//! House rules for the battle formula.
+
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use serde::{Deserialize, Serialize};
+
+use super::TypeChart;
+
+/// Knobs for the battle formula. [`BattleRules::classic`] is the game's
+/// standard ruleset; event organisers can load their own from any serde
+/// format, with missing fields taken from classic.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BattleRules {
+    /// Sides on the attack and block dice
+    pub die_sides: i16,
+    /// LUCK is divided by this and added to both rolls
+    pub luck_divisor: i16,
+    /// Added to the attack roll when the attacker's element has the advantage
+    pub advantage_bonus: i16,
+    /// Crit damage multiplier
+    pub crit_multiplier: i16,
+    /// Every hit does at least this much damage
+    pub min_damage: i16,
+    /// Turns after which the battle is decided without a knockout
+    pub turn_cap: u32,
+    pub initiative: Initiative,
+    /// Who goes first under [`Initiative::Alternating`]
+    pub first_move: FirstMove,
+    pub tie_break: TieBreak,
+    /// Monsters pick from their [`MoveSet`](crate::MoveSet) instead of
+    /// always attacking
+    pub moves: bool,
+    /// Element effectiveness; super effective attacks also get
+    /// `advantage_bonus` to hit
+    pub type_chart: TypeChart,
+    /// Percent chance a hit leaves the attacker's element
+    /// [status](super::StatusKind) on the defender, doubled for special
+    /// moves; 0 turns statuses off
+    pub status_chance: i16,
+    /// Percent chance per point of LUCK to shrug off a status
+    pub resist_per_luck: i16,
+    /// Attacks roll to hit first, with SPD and LUCK deciding hits, grazes
+    /// (half damage) and misses; LUCK also adds half its value to crit
+    /// chance and gives a once-a-battle chance to survive a knockout
+    pub evasion: bool,
+    /// In team battles, a monster below this percent of its starting HP
+    /// spends its action switching out for a healthier teammate; 0 never
+    /// switches
+    pub switch_below: i16,
+}
+
+/// How actions are scheduled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Initiative {
+    /// One action each per turn, in [`FirstMove`] order
+    Alternating,
+    /// Actions come round every `6000 / (SPD + 40)` ticks of a 100-tick turn,
+    /// so a 60 SPD monster acts about twice as often as a 7 SPD one. Ties go
+    /// to a coin flip from the battle RNG.
+    Timeline,
+}
+
+/// Who acts first each turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FirstMove {
+    /// Higher SPD; the first monster passed in wins ties
+    Faster,
+    /// The first monster passed in, always
+    Challenger,
+    /// A coin flip from the battle RNG before the first turn
+    CoinFlip,
+}
+
+/// How a timed-out battle that's level on HP is decided
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TieBreak {
+    /// Level on HP is a draw
+    Draw,
+    /// Higher SPD wins, then higher ATK; a draw only if both match
+    Stats,
+}
+
+impl BattleRules {
+    /// The standard rules: d20s, half LUCK on both rolls, +2 for element
+    /// advantage, double-damage crits, at least 1 damage a hit, 50 turns,
+    /// alternating turns with the faster monster first, and SPD/ATK tiebreaks
+    pub fn classic() -> Self {
+        BattleRules {
+            die_sides: 20,
+            luck_divisor: 2,
+            advantage_bonus: 2,
+            crit_multiplier: 2,
+            min_damage: 1,
+            turn_cap: 50,
+            initiative: Initiative::Alternating,
+            first_move: FirstMove::Faster,
+            tie_break: TieBreak::Stats,
+            moves: false,
+            type_chart: TypeChart::classic(),
+            status_chance: 0,
+            resist_per_luck: 3,
+            evasion: false,
+            switch_below: 0,
+        }
+    }
+
+    /// Classic plus move sets, the balanced [`TypeChart`], a 20% status
+    /// chance, the SPD timeline, evasion and switching out below 25% HP;
+    /// what the app plays
+    pub fn standard() -> Self {
+        BattleRules {
+            moves: true,
+            type_chart: TypeChart::balanced(),
+            status_chance: 20,
+            initiative: Initiative::Timeline,
+            evasion: true,
+            switch_below: 25,
+            ..BattleRules::classic()
+        }
+    }
+
+    /// Check every knob is in a range the engine is built for. Rules loaded
+    /// from outside the game have to pass this before a battle runs on them.
+    pub fn validate(&self) -> Result<(), RulesError> {
+        for (rule, value, allowed) in [
+            ("die_sides", self.die_sides, 1..=100),
+            ("luck_divisor", self.luck_divisor, 1..=20),
+            ("advantage_bonus", self.advantage_bonus, 0..=20),
+            ("crit_multiplier", self.crit_multiplier, 1..=5),
+            ("min_damage", self.min_damage, 0..=20),
+            ("status_chance", self.status_chance, 0..=100),
+            ("resist_per_luck", self.resist_per_luck, 0..=100),
+            ("switch_below", self.switch_below, 0..=100),
+        ] {
+            check_rule(rule, i64::from(value), allowed)?;
+        }
+        check_rule(
+            "turn_cap",
+            i64::from(self.turn_cap),
+            1..=i64::from(MAX_TURN_CAP),
+        )
+    }
+
+    /// Fingerprint of these rules, recorded with every battle so results
+    /// from different rulesets can't be mixed up
+    pub fn hash(&self) -> [u8; 32] {
+        let bytes = bincode::serialize(self).expect("rules always serialize");
+        blake3::derive_key("qrmonsters battle rules v1", &bytes)
+    }
+}
+
+impl Default for BattleRules {
+    fn default() -> Self {
+        BattleRules::classic()
+    }
+}
+
+/// Longest battle [`BattleRules::validate`] allows
+pub const MAX_TURN_CAP: u32 = 1000;
+
+/// A rule outside the range [`BattleRules::validate`] allows for it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RulesError {
+    pub rule: &'static str,
+    pub value: i64,
+    pub allowed: RangeInclusive<i64>,
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} out of range: {} (allowed {}..={})",
+            self.rule,
+            self.value,
+            self.allowed.start(),
+            self.allowed.end()
+        )
+    }
+}
+
+impl std::error::Error for RulesError {}
+
+fn check_rule(
+    rule: &'static str,
+    value: i64,
+    allowed: RangeInclusive<i64>,
+) -> Result<(), RulesError> {
+    if allowed.contains(&value) {
+        Ok(())
+    } else {
+        Err(RulesError {
+            rule,
+            value,
+            allowed,
+        })
+    }
+}
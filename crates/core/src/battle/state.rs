@@ -0,0 +1,531 @@
+//! A battle one action at a time, so players (or strategies) can decide each
+//! move as it comes up.
+
+use std::fmt;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{
+    action_delay, choose_action, decide, format_log, legal_actions, send_in_replacements,
+    strategy_rng, take_move, team_key, Action, BattleConfig, BattleEvent, BattleResult,
+    BattleRules, BattleStrategy, BattleView, FirstMove, Initiative, Side, StatusKind, TeamSide,
+    TURN_TIME,
+};
+use crate::Monster;
+
+/// A battle in progress. [`BattleState::to_act`] says whose decision it is;
+/// [`BattleState::apply`] carries it out and runs the battle on to the next
+/// decision.
+#[derive(Debug, Clone)]
+pub struct BattleState {
+    rules: BattleRules,
+    seed: [u8; 32],
+    rng: ChaCha8Rng,
+    /// Separate stream for [`BattleState::step_with`]'s strategies, so the
+    /// battle itself only depends on the actions taken
+    strategy_rng: ChaCha8Rng,
+    sides: [Side; 2],
+    events: Vec<BattleEvent>,
+    turn: u32,
+    clock: Clock,
+    /// Side waiting on an action; `None` once the battle is over
+    to_act: Option<usize>,
+    /// Who was out front when the current action started
+    fronts: [usize; 2],
+    /// Every action applied so far
+    actions: Vec<Action>,
+}
+
+/// Who acts next
+#[derive(Debug, Clone)]
+enum Clock {
+    /// `first` leads every turn; `second` once it has gone this turn
+    Alternating { first: usize, second: bool },
+    /// Each side's next slot, and the slot being acted in
+    Timeline { next: [u32; 2], now: u32 },
+}
+
+/// Why [`BattleState::apply`] turned an action down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BattleError {
+    /// The battle is over
+    Finished,
+    /// Not in [`BattleState::legal_actions`]
+    IllegalAction(Action),
+}
+
+impl fmt::Display for BattleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BattleError::Finished => write!(f, "the battle is over"),
+            BattleError::IllegalAction(action) => write!(f, "{action:?} isn't allowed now"),
+        }
+    }
+}
+
+impl std::error::Error for BattleError {}
+
+/// Where a battle stands, for showing it between actions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BattleSnapshot {
+    /// Turn in progress; 0 before the first
+    pub turn: u32,
+    /// `None` once the battle is over
+    pub to_act: Option<TeamSide>,
+    /// Side A, then side B
+    pub sides: [SideSnapshot; 2],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SideSnapshot {
+    /// Index into `members` of the monster out front
+    pub active: usize,
+    pub members: Vec<MemberSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemberSnapshot {
+    pub monster: Uuid,
+    pub hp: i16,
+    pub max_hp: i16,
+    /// DEF bonus from a guard move
+    pub guard: i16,
+    /// Uses left of each move, indexed by [`crate::MoveKind`]; `None` is
+    /// unlimited
+    pub uses: [Option<u8>; 4],
+    /// Each status and the moves it has left
+    pub statuses: Vec<(StatusKind, u8)>,
+}
+
+impl BattleState {
+    /// Start a battle between `a` and `b`, seeded like [`super::battle_with`]
+    pub fn new(a: &Monster, b: &Monster, config: &BattleConfig) -> Self {
+        let seed = config.seed_for(a.id, b.id);
+        Self::start(
+            std::slice::from_ref(a),
+            std::slice::from_ref(b),
+            seed,
+            &config.rules,
+        )
+    }
+
+    /// Start a battle between teams `a` and `b`, seeded like
+    /// [`super::team_battle_with`]
+    pub fn new_team(a: &[Monster], b: &[Monster], config: &BattleConfig) -> Self {
+        let seed = config.seed_for(team_key(a), team_key(b));
+        Self::start(a, b, seed, &config.rules)
+    }
+
+    fn start(a: &[Monster], b: &[Monster], seed: [u8; 32], rules: &BattleRules) -> Self {
+        let mut rng = ChaCha8Rng::from_seed(seed);
+        let sides = [Side::new(a, rules), Side::new(b, rules)];
+        let over = sides.iter().any(Side::defeated);
+
+        let clock = match rules.initiative {
+            // nobody to flip for in a battle that's already over
+            _ if over => Clock::Alternating {
+                first: 0,
+                second: false,
+            },
+            Initiative::Alternating => {
+                let (a, b) = (&sides[0].front().mon, &sides[1].front().mon);
+                let a_first = match rules.first_move {
+                    FirstMove::Faster => a.stats.spd >= b.stats.spd,
+                    FirstMove::Challenger => true,
+                    FirstMove::CoinFlip => rng.gen_bool(0.5),
+                };
+                Clock::Alternating {
+                    first: if a_first { 0 } else { 1 },
+                    second: false,
+                }
+            }
+            Initiative::Timeline => Clock::Timeline {
+                next: [
+                    action_delay(&sides[0].front().mon),
+                    action_delay(&sides[1].front().mon),
+                ],
+                now: 0,
+            },
+        };
+
+        let mut state = BattleState {
+            rules: rules.clone(),
+            seed,
+            rng,
+            strategy_rng: strategy_rng(seed),
+            sides,
+            events: Vec::new(),
+            turn: 0,
+            clock,
+            to_act: None,
+            fronts: [0, 0],
+            actions: Vec::new(),
+        };
+        if !over {
+            state.advance();
+        }
+        state
+    }
+
+    /// Side whose decision it is; `None` once the battle is over
+    pub fn to_act(&self) -> Option<TeamSide> {
+        self.to_act.map(team_side)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.to_act.is_none()
+    }
+
+    /// What the side to act may do; empty once the battle is over
+    pub fn legal_actions(&self) -> Vec<Action> {
+        let Some(actor) = self.to_act else {
+            return Vec::new();
+        };
+        let me = &self.sides[actor];
+        legal_actions(me.front(), &bench(me))
+    }
+
+    /// What a [`BattleStrategy`] would see deciding for the side to act
+    pub fn view(&self) -> Option<BattleView<'_>> {
+        let actor = self.to_act?;
+        let (me, foe) = (&self.sides[actor], &self.sides[1 - actor]);
+        let bench = bench(me);
+        Some(BattleView {
+            turn: self.turn,
+            rules: &self.rules,
+            me: me.front().view(),
+            foe: foe.front().view(),
+            legal: legal_actions(me.front(), &bench),
+            bench: bench.into_iter().map(|(i, f)| (i, f.view())).collect(),
+        })
+    }
+
+    /// Take `action` for the side to act, then play on until someone has a
+    /// decision to make or the battle ends
+    pub fn apply(&mut self, action: Action) -> Result<(), BattleError> {
+        let Some(actor) = self.to_act else {
+            return Err(BattleError::Finished);
+        };
+        if !self.legal_actions().contains(&action) {
+            return Err(BattleError::IllegalAction(action));
+        }
+        self.to_act = None;
+        self.actions.push(action);
+
+        let turn = self.turn;
+        let [a, b] = &mut self.sides;
+        let (me, foe) = if actor == 0 { (a, b) } else { (b, a) };
+        match action {
+            Action::Switch(next) => {
+                self.events.push(BattleEvent::SwitchIn {
+                    turn,
+                    monster: me.roster[next].mon.id,
+                    replacing: me.front().mon.id,
+                    forced: false,
+                });
+                me.active = next;
+            }
+            Action::Move(kind) => take_move(
+                &mut self.rng,
+                &self.rules,
+                me.front_mut(),
+                foe.front_mut(),
+                kind,
+                &mut self.events,
+                turn,
+            ),
+        }
+        if self.end_action(actor) {
+            self.advance();
+        }
+        Ok(())
+    }
+
+    /// Let `strategy` decide for the side to act, and apply its choice. It
+    /// isn't asked when attacking is the only option, and anything not legal
+    /// counts as an attack. Returns the action taken.
+    pub fn step_with(&mut self, strategy: &mut dyn BattleStrategy) -> Option<Action> {
+        let actor = self.to_act?;
+        let (me, foe) = (&self.sides[actor], &self.sides[1 - actor]);
+        let action = choose_action(
+            &mut self.strategy_rng,
+            &self.rules,
+            strategy,
+            me.front(),
+            bench(me),
+            foe.front(),
+            self.turn,
+        );
+        self.apply(action)
+            .expect("choose_action picks a legal action");
+        Some(action)
+    }
+
+    /// Let `strategies` decide for sides A and B until the battle is over
+    pub fn play_out(&mut self, strategies: [&mut dyn BattleStrategy; 2]) {
+        while let Some(actor) = self.to_act {
+            self.step_with(&mut *strategies[actor]);
+        }
+    }
+
+    pub fn snapshot(&self) -> BattleSnapshot {
+        let side = |s: &Side| SideSnapshot {
+            active: s.active,
+            members: s
+                .roster
+                .iter()
+                .map(|f| MemberSnapshot {
+                    monster: f.mon.id,
+                    hp: f.hp.max(0),
+                    max_hp: f.mon.stats.hp,
+                    guard: f.guard,
+                    uses: f.uses,
+                    statuses: f.statuses.iter().map(|s| (s.kind, s.moves_left)).collect(),
+                })
+                .collect(),
+        };
+        BattleSnapshot {
+            turn: self.turn,
+            to_act: self.to_act(),
+            sides: [side(&self.sides[0]), side(&self.sides[1])],
+        }
+    }
+
+    /// Everything that has happened so far
+    pub fn events(&self) -> &[BattleEvent] {
+        &self.events
+    }
+
+    /// Every action applied so far, in order
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    pub fn turn(&self) -> u32 {
+        self.turn
+    }
+
+    pub fn rules(&self) -> &BattleRules {
+        &self.rules
+    }
+
+    /// Every monster taking part, side A's team then side B's
+    pub fn monsters(&self) -> Vec<&Monster> {
+        self.sides
+            .iter()
+            .flat_map(|s| s.roster.iter().map(|f| &f.mon))
+            .collect()
+    }
+
+    /// Score a one-on-one battle between the two leads. Meant for once the
+    /// battle [is finished](Self::is_finished); before that it's scored as
+    /// if time ran out now.
+    pub fn into_result(mut self) -> BattleResult {
+        let (a, b) = (self.sides[0].front(), self.sides[1].front());
+        let [a_hp, b_hp] = [a.hp, b.hp];
+        let outcome = decide(&a.mon, &b.mon, a_hp, b_hp, self.rules.tie_break);
+        self.events.push(BattleEvent::Outcome {
+            outcome,
+            turns: self.turn,
+        });
+        let log = format_log(&self.events, &[&a.mon, &b.mon]);
+
+        BattleResult {
+            outcome,
+            turns: self.turn,
+            events: self.events,
+            log,
+            a_remaining_hp: a_hp.max(0),
+            b_remaining_hp: b_hp.max(0),
+            actions: self.actions,
+            seed: self.seed,
+            rules_hash: self.rules.hash(),
+        }
+    }
+
+    pub(super) fn seed(&self) -> [u8; 32] {
+        self.seed
+    }
+
+    pub(super) fn sides(&self) -> &[Side; 2] {
+        &self.sides
+    }
+
+    pub(super) fn into_events(self) -> Vec<BattleEvent> {
+        self.events
+    }
+
+    pub(super) fn push_event(&mut self, event: BattleEvent) {
+        self.events.push(event);
+    }
+
+    /// Play on to the next decision, through stuns and burn knockouts
+    fn advance(&mut self) {
+        while let Some(actor) = self.next_actor() {
+            self.fronts = [self.sides[0].active, self.sides[1].active];
+            let front = self.sides[actor].front_mut();
+            front.guard = 0;
+            if front.start_move(&mut self.events, self.turn) {
+                self.to_act = Some(actor);
+                return;
+            }
+            if !self.end_action(actor) {
+                return;
+            }
+        }
+    }
+
+    /// Move the clock on to whoever acts next. `None` at the turn cap.
+    fn next_actor(&mut self) -> Option<usize> {
+        match &mut self.clock {
+            Clock::Alternating { first, second } => {
+                let actor = if *second { 1 - *first } else { *first };
+                if !*second {
+                    if self.turn >= self.rules.turn_cap {
+                        return None;
+                    }
+                    self.turn += 1;
+                    self.events.push(BattleEvent::TurnStart { turn: self.turn });
+                }
+                *second = !*second;
+                Some(actor)
+            }
+            Clock::Timeline { next, now } => {
+                *now = next[0].min(next[1]);
+                let now_turn = now.div_ceil(TURN_TIME);
+                if now_turn > self.rules.turn_cap {
+                    return None;
+                }
+                while self.turn < now_turn {
+                    self.turn += 1;
+                    self.events.push(BattleEvent::TurnStart { turn: self.turn });
+                }
+
+                let tie = next[0] == next[1];
+                let actor = match (tie, next[0] < next[1]) {
+                    (true, _) => usize::from(self.rng.gen_bool(0.5)),
+                    (false, true) => 0,
+                    (false, false) => 1,
+                };
+                self.events.push(BattleEvent::Initiative {
+                    turn: self.turn,
+                    monster: self.sides[actor].front().mon.id,
+                    time: *now,
+                    tie,
+                });
+                Some(actor)
+            }
+        }
+    }
+
+    /// Wrap up `actor`'s action: statuses count down, fainted monsters are
+    /// replaced and the timeline moves on. False once a side is defeated.
+    fn end_action(&mut self, actor: usize) -> bool {
+        let active = self.fronts[actor];
+        self.sides[actor].roster[active].end_move(&mut self.events, self.turn);
+        if !send_in_replacements(&mut self.sides, &mut self.events, self.turn) {
+            return false;
+        }
+        // the actor, and anyone fresh out front, get their next slot from now
+        if let Clock::Timeline { next, now } = &mut self.clock {
+            for (i, side) in self.sides.iter().enumerate() {
+                if i == actor || side.active != self.fronts[i] {
+                    next[i] = *now + action_delay(&side.front().mon);
+                }
+            }
+        }
+        true
+    }
+}
+
+/// `side`'s conscious monsters other than the one out front
+fn bench(side: &Side) -> Vec<(usize, &super::Fighter)> {
+    side.roster
+        .iter()
+        .enumerate()
+        .filter(|&(i, f)| i != side.active && f.hp > 0)
+        .collect()
+}
+
+fn team_side(index: usize) -> TeamSide {
+    if index == 0 {
+        TeamSide::A
+    } else {
+        TeamSide::B
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{battle_with, generate_monster, AutoStrategy, MoveKind};
+
+    #[test]
+    fn test_stepping_matches_auto_battle() {
+        let a = generate_monster([21; 32]);
+        let b = generate_monster([22; 32]);
+        for rules in [BattleRules::classic(), BattleRules::standard()] {
+            let config = BattleConfig {
+                rules,
+                ..BattleConfig::on_day(20_000)
+            };
+            let mut state = BattleState::new(&a, &b, &config);
+            let mut strategies = [AutoStrategy, AutoStrategy];
+            while let Some(side) = state.to_act() {
+                let legal = state.legal_actions();
+                assert!(!legal.is_empty());
+                let i = if side == TeamSide::A { 0 } else { 1 };
+                let action = state.step_with(&mut strategies[i]).unwrap();
+                assert!(legal.contains(&action));
+            }
+            assert!(state.is_finished());
+            assert!(state.legal_actions().is_empty());
+            assert_eq!(
+                state.apply(Action::Move(MoveKind::Attack)),
+                Err(BattleError::Finished)
+            );
+
+            let stepped = state.into_result();
+            let auto = battle_with(&a, &b, &config);
+            assert_eq!(stepped.events, auto.events);
+            assert_eq!(stepped.outcome, auto.outcome);
+        }
+    }
+
+    #[test]
+    fn test_apply_checks_actions() {
+        let a = generate_monster([23; 32]);
+        let b = generate_monster([24; 32]);
+        let config = BattleConfig {
+            rules: BattleRules::standard(),
+            ..BattleConfig::on_day(20_000)
+        };
+        let mut state = BattleState::new(&a, &b, &config);
+        assert!(state.snapshot().turn >= 1);
+        assert_eq!(
+            state.apply(Action::Switch(1)),
+            Err(BattleError::IllegalAction(Action::Switch(1)))
+        );
+
+        let side = state.to_act().unwrap();
+        let before = state.events().len();
+        state.apply(Action::Move(MoveKind::Guard)).unwrap();
+        assert!(state.events()[before..].iter().any(|e| matches!(
+            e,
+            BattleEvent::Guard { monster, .. }
+                if *monster == if side == TeamSide::A { a.id } else { b.id }
+        )));
+
+        let snapshot = state.snapshot();
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert_eq!(
+            serde_json::from_str::<BattleSnapshot>(&json).unwrap(),
+            snapshot
+        );
+        let member = &snapshot.sides[0].members[0];
+        assert_eq!((member.monster, member.max_hp), (a.id, a.stats.hp));
+    }
+}
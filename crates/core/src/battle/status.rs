@@ -0,0 +1,83 @@
+//! Status effects that element attacks leave behind.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Element;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StatusKind {
+    /// Fire: loses [`BURN_DAMAGE`] HP at the start of each move
+    Burn,
+    /// Water: -2 on attack rolls
+    Soak,
+    /// Electric: skips its next move
+    Stun,
+    /// Air: loses any guard and -3 on its next attack roll
+    Knockback,
+    /// Earth: -2 on block rolls
+    Shaken,
+}
+
+/// HP a burn takes each move
+pub const BURN_DAMAGE: i16 = 2;
+
+impl StatusKind {
+    /// The status an attacker of this element inflicts
+    pub fn for_element(element: Element) -> Option<StatusKind> {
+        match element {
+            Element::Fire => Some(StatusKind::Burn),
+            Element::Water => Some(StatusKind::Soak),
+            Element::Electric => Some(StatusKind::Stun),
+            Element::Air => Some(StatusKind::Knockback),
+            Element::Earth => Some(StatusKind::Shaken),
+            Element::Unknown(_) => None,
+        }
+    }
+
+    /// How many of the victim's moves it lasts
+    pub fn duration(self) -> u8 {
+        match self {
+            StatusKind::Burn | StatusKind::Soak | StatusKind::Shaken => 3,
+            StatusKind::Stun | StatusKind::Knockback => 1,
+        }
+    }
+
+    /// Modifier to the victim's attack rolls
+    pub(super) fn hit_penalty(self) -> i16 {
+        match self {
+            StatusKind::Soak => 2,
+            StatusKind::Knockback => 3,
+            _ => 0,
+        }
+    }
+
+    /// Modifier to the victim's block rolls
+    pub(super) fn block_penalty(self) -> i16 {
+        match self {
+            StatusKind::Shaken => 2,
+            _ => 0,
+        }
+    }
+}
+
+impl fmt::Display for StatusKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            StatusKind::Burn => "burned",
+            StatusKind::Soak => "soaked",
+            StatusKind::Stun => "stunned",
+            StatusKind::Knockback => "knocked back",
+            StatusKind::Shaken => "shaken",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A status on a monster, with the moves it has left to run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Status {
+    pub kind: StatusKind,
+    pub moves_left: u8,
+}
@@ -0,0 +1,389 @@
+//! Who decides what each side does.
+//!
+//! A [`BattleStrategy`] sees a read-only [`BattleView`] of its side of the
+//! battle and picks one of the legal [`Action`]s. Strategies only get
+//! randomness from the battle RNG they're handed, so a battle replays exactly
+//! from its seed.
+
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use super::{BattleRules, Effect, StatusKind};
+use crate::{Monster, MoveKind, MoveSet};
+
+/// One thing a monster can do with its action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    /// Use a move; with moves off, `Move(MoveKind::Attack)` is the only one
+    Move(MoveKind),
+    /// Swap the front monster for the teammate at this roster index
+    Switch(usize),
+}
+
+/// What a strategy can see of one monster
+#[derive(Debug, Clone)]
+pub struct FighterView<'a> {
+    pub monster: &'a Monster,
+    pub hp: i16,
+    /// DEF bonus from a guard move
+    pub guard: i16,
+    /// `None` when the rules have monsters only attack
+    pub moves: Option<&'a MoveSet>,
+    /// Uses left of each move, indexed by [`MoveKind`]; `None` is unlimited
+    pub uses: [Option<u8>; 4],
+    /// Statuses and how many of its moves each has left
+    pub statuses: Vec<(StatusKind, u8)>,
+}
+
+impl FighterView<'_> {
+    pub fn can_use(&self, kind: MoveKind) -> bool {
+        self.uses[kind as usize] != Some(0)
+    }
+
+    /// HP as a fraction of starting HP, in thousandths
+    pub fn health(&self) -> i32 {
+        i32::from(self.hp) * 1000 / i32::from(self.monster.stats.hp.max(1))
+    }
+
+    fn power(&self, kind: MoveKind) -> i16 {
+        self.moves.map_or(0, |set| set.get(kind).power)
+    }
+
+    fn hit_penalty(&self) -> i16 {
+        self.statuses.iter().map(|(s, _)| s.hit_penalty()).sum()
+    }
+
+    fn block_penalty(&self) -> i16 {
+        self.statuses.iter().map(|(s, _)| s.block_penalty()).sum()
+    }
+
+    /// Attack moves it can use right now
+    fn attacks(&self) -> Vec<MoveKind> {
+        if self.moves.is_none() {
+            return vec![MoveKind::Attack];
+        }
+        [MoveKind::Attack, MoveKind::Special]
+            .into_iter()
+            .filter(|&k| self.can_use(k))
+            .collect()
+    }
+}
+
+/// A side's read-only view of the battle when it's time to act
+#[derive(Debug, Clone)]
+pub struct BattleView<'a> {
+    pub turn: u32,
+    pub rules: &'a BattleRules,
+    /// The monster about to act
+    pub me: FighterView<'a>,
+    /// The monster it's up against
+    pub foe: FighterView<'a>,
+    /// Conscious benched teammates, with their roster index
+    pub bench: Vec<(usize, FighterView<'a>)>,
+    /// Everything `me` may do; anything else is treated as an attack
+    pub legal: Vec<Action>,
+}
+
+pub trait BattleStrategy {
+    /// Pick one of `view.legal`. Use only `rng` for randomness.
+    fn choose(&mut self, view: &BattleView, rng: &mut dyn RngCore) -> Action;
+}
+
+/// The engine's default: switch out below [`BattleRules::switch_below`],
+/// heal below a third of starting HP, otherwise mostly attack, sometimes
+/// guard and spend specials while they last
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AutoStrategy;
+
+impl BattleStrategy for AutoStrategy {
+    fn choose(&mut self, view: &BattleView, rng: &mut dyn RngCore) -> Action {
+        let me = &view.me;
+        if me.health() * 100 < i32::from(view.rules.switch_below) * 1000 {
+            let best = view
+                .bench
+                .iter()
+                .filter(|(_, f)| f.health() > me.health())
+                .max_by_key(|&&(i, ref f)| (f.health(), std::cmp::Reverse(i)));
+            if let Some(&(i, _)) = best {
+                return Action::Switch(i);
+            }
+        }
+        if me.moves.is_none() {
+            return Action::Move(MoveKind::Attack);
+        }
+        if me.hp * 3 < me.monster.stats.hp && me.can_use(MoveKind::Heal) {
+            return Action::Move(MoveKind::Heal);
+        }
+        Action::Move(match rng.gen_range(0..100) {
+            0..=14 => MoveKind::Guard,
+            15..=39 if me.can_use(MoveKind::Special) => MoveKind::Special,
+            _ => MoveKind::Attack,
+        })
+    }
+}
+
+/// Easy: any legal action, uniformly at random
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomStrategy;
+
+impl BattleStrategy for RandomStrategy {
+    fn choose(&mut self, view: &BattleView, rng: &mut dyn RngCore) -> Action {
+        *view
+            .legal
+            .choose(rng)
+            .unwrap_or(&Action::Move(MoveKind::Attack))
+    }
+}
+
+/// Normal: the action worth the most HP this move, on average
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GreedyStrategy;
+
+impl BattleStrategy for GreedyStrategy {
+    fn choose(&mut self, view: &BattleView, _rng: &mut dyn RngCore) -> Action {
+        best_by(view, |action| immediate_value(view, action))
+    }
+}
+
+/// Hard: also weighs the opponent's best reply to each action
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LookaheadStrategy;
+
+impl BattleStrategy for LookaheadStrategy {
+    fn choose(&mut self, view: &BattleView, _rng: &mut dyn RngCore) -> Action {
+        best_by(view, |action| {
+            let (me, foe_hp, gain) = expected_after(view, action);
+            if foe_hp <= 0.0 {
+                return gain + KO_BONUS;
+            }
+            // the foe answers with its hardest-hitting attack
+            let reply = view
+                .foe
+                .attacks()
+                .into_iter()
+                .map(|k| expected_damage(view.rules, &view.foe, &me, view.foe.power(k)))
+                .fold(0.0, f32::max);
+            let fainted = if reply >= f32::from(me.hp) {
+                KO_BONUS
+            } else {
+                0.0
+            };
+            gain - reply.min(f32::from(me.hp)) - fainted
+        })
+    }
+}
+
+/// Built-in opponents, weakest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn strategy(self) -> Box<dyn BattleStrategy> {
+        match self {
+            Difficulty::Easy => Box::new(RandomStrategy),
+            Difficulty::Normal => Box::new(GreedyStrategy),
+            Difficulty::Hard => Box::new(LookaheadStrategy),
+        }
+    }
+}
+
+/// Value of knocking the foe out, on top of the damage
+const KO_BONUS: f32 = 10.0;
+
+/// The legal action with the highest score; the first one wins ties
+fn best_by(view: &BattleView, score: impl Fn(Action) -> f32) -> Action {
+    let mut best = (Action::Move(MoveKind::Attack), f32::NEG_INFINITY);
+    for &action in &view.legal {
+        let value = score(action);
+        if value > best.1 {
+            best = (action, value);
+        }
+    }
+    best.0
+}
+
+fn immediate_value(view: &BattleView, action: Action) -> f32 {
+    let (me, foe_hp, gain) = expected_after(view, action);
+    match action {
+        Action::Switch(_) => {
+            // only worth it to dodge a knockout
+            let incoming = incoming_damage(view, &view.me);
+            if incoming >= f32::from(view.me.hp) {
+                f32::from(me.hp) / 4.0
+            } else {
+                -1.0
+            }
+        }
+        Action::Move(MoveKind::Guard) => {
+            incoming_damage(view, &view.me) - incoming_damage(view, &me)
+        }
+        Action::Move(MoveKind::Heal) if view.me.hp * 2 >= view.me.monster.stats.hp => gain / 2.0,
+        _ if foe_hp <= 0.0 => gain + KO_BONUS,
+        _ => gain,
+    }
+}
+
+/// What things look like on average after `action`: the acting side's front
+/// monster, the foe's HP, and HP gained or dealt
+fn expected_after<'a>(view: &BattleView<'a>, action: Action) -> (FighterView<'a>, f32, f32) {
+    let mut me = view.me.clone();
+    let foe_hp = f32::from(view.foe.hp);
+    match action {
+        Action::Switch(i) => {
+            let benched = view.bench.iter().find(|(j, _)| *j == i);
+            let me = benched.map_or(me, |(_, f)| f.clone());
+            (me, foe_hp, 0.0)
+        }
+        Action::Move(MoveKind::Guard) => {
+            me.guard = view.me.power(MoveKind::Guard);
+            (me, foe_hp, 0.0)
+        }
+        Action::Move(MoveKind::Heal) => {
+            let healed = view
+                .me
+                .power(MoveKind::Heal)
+                .min(me.monster.stats.hp - me.hp)
+                .max(0);
+            me.hp += healed;
+            (me, foe_hp, f32::from(healed))
+        }
+        Action::Move(kind) => {
+            let dmg = expected_damage(view.rules, &me, &view.foe, view.me.power(kind)).min(foe_hp);
+            (me, foe_hp - dmg, dmg)
+        }
+    }
+}
+
+/// The foe's hardest-hitting attack on `me`, on average
+fn incoming_damage(view: &BattleView, me: &FighterView) -> f32 {
+    view.foe
+        .attacks()
+        .into_iter()
+        .map(|k| expected_damage(view.rules, &view.foe, me, view.foe.power(k)))
+        .fold(0.0, f32::max)
+}
+
+/// Average damage from `atk` hitting `def`, from average rolls
+fn expected_damage(rules: &BattleRules, atk: &FighterView, def: &FighterView, power: i16) -> f32 {
+    let (a, d) = (atk.monster, def.monster);
+    let die = (f32::from(rules.die_sides.max(1)) + 1.0) / 2.0;
+    let luck_divisor = rules.luck_divisor.max(1);
+    let effect = rules.type_chart.effect(a.element, d.element);
+    let adv = if effect == Effect::Super {
+        rules.advantage_bonus
+    } else {
+        0
+    };
+    // in f32 throughout, so house rules can't overflow the sums
+    let hit = die
+        + f32::from(a.stats.atk)
+        + f32::from(a.stats.luck / luck_divisor)
+        + f32::from(adv)
+        + f32::from(power)
+        - f32::from(atk.hit_penalty());
+    let block = die
+        + f32::from(d.stats.def)
+        + f32::from(d.stats.luck / luck_divisor)
+        + f32::from(def.guard)
+        - f32::from(def.block_penalty());
+    let base = (hit - block).max(f32::from(rules.min_damage));
+    let percent = f32::from(rules.type_chart.multiplier(a.element, d.element));
+    let crit = f32::from(a.stats.crit.clamp(0, 100)) / 100.0;
+    base * percent / 100.0 * (1.0 + crit * (f32::from(rules.crit_multiplier) - 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{battle_with_strategies, generate_monster, BattleConfig};
+
+    fn fighter<'a>(mon: &'a Monster, set: &'a MoveSet) -> FighterView<'a> {
+        FighterView {
+            monster: mon,
+            hp: mon.stats.hp,
+            guard: 0,
+            moves: Some(set),
+            uses: MoveKind::ALL.map(|k| set.get(k).uses),
+            statuses: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_greedy_picks_the_biggest_hit() {
+        let rules = BattleRules::standard();
+        let a = generate_monster([90u8; 32]);
+        let mut b = generate_monster([91u8; 32]);
+        b.stats.hp = 500;
+        b.stats.def = 0;
+        let (set_a, set_b) = (a.move_set(), b.move_set());
+        let mut view = BattleView {
+            turn: 1,
+            rules: &rules,
+            me: fighter(&a, &set_a),
+            foe: fighter(&b, &set_b),
+            bench: Vec::new(),
+            legal: MoveKind::ALL.map(Action::Move).to_vec(),
+        };
+        let mut rng = rand::rngs::mock::StepRng::new(0, 1);
+
+        let biggest = if set_a.special.power > set_a.attack.power {
+            MoveKind::Special
+        } else {
+            MoveKind::Attack
+        };
+        assert_eq!(
+            GreedyStrategy.choose(&view, &mut rng),
+            Action::Move(biggest)
+        );
+
+        view.foe.hp = 1;
+        assert!(matches!(
+            LookaheadStrategy.choose(&view, &mut rng),
+            Action::Move(MoveKind::Attack | MoveKind::Special)
+        ));
+
+        view.legal = vec![Action::Move(MoveKind::Guard)];
+        assert_eq!(
+            GreedyStrategy.choose(&view, &mut rng),
+            Action::Move(MoveKind::Guard)
+        );
+    }
+
+    #[test]
+    fn test_strategies_are_deterministic_and_ranked() {
+        let config = BattleConfig {
+            rules: BattleRules::standard(),
+            ..BattleConfig::on_day(20_000)
+        };
+        let a = generate_monster([92u8; 32]);
+        let b = generate_monster([93u8; 32]);
+        let run = || {
+            battle_with_strategies(&a, &b, &config, [&mut RandomStrategy, &mut RandomStrategy])
+                .events
+        };
+        assert_eq!(run(), run());
+
+        // hard beats easy more often than not, playing both sides
+        let (mut hard, mut easy) = (0, 0);
+        for i in 0..30u8 {
+            let x = generate_monster([100 + i; 32]);
+            let y = generate_monster([200 - i; 32]);
+            for (first, second) in [(&x, &y), (&y, &x)] {
+                let mut h = Difficulty::Hard.strategy();
+                let mut e = Difficulty::Easy.strategy();
+                let res = battle_with_strategies(first, second, &config, [&mut *h, &mut *e]);
+                match res.winner() {
+                    Some(id) if id == first.id => hard += 1,
+                    Some(_) => easy += 1,
+                    None => {}
+                }
+            }
+        }
+        assert!(hard > easy, "hard {hard}, easy {easy}");
+    }
+}
@@ -0,0 +1,248 @@
+//! Team battles: each side fields an ordered team, and fainted monsters are
+//! replaced by the next one in line until a side runs out.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{
+    format_log, AutoStrategy, BattleConfig, BattleEvent, BattleLine, BattleState, BattleStrategy,
+    Side,
+};
+use crate::Monster;
+
+/// Team size the app picks for team battles. The engine takes any size.
+pub const TEAM_BATTLE_SIZE: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TeamSide {
+    A,
+    B,
+}
+
+/// How a team battle ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TeamOutcome {
+    /// The loser has no conscious monsters left
+    Knockout { winner: TeamSide },
+    /// The turn cap was reached and `winner` had more monsters standing, or
+    /// as many with more total HP
+    Points { winner: TeamSide },
+    /// The turn cap was reached level on both
+    Draw,
+}
+
+impl TeamOutcome {
+    pub fn winner(&self) -> Option<TeamSide> {
+        match *self {
+            TeamOutcome::Knockout { winner } | TeamOutcome::Points { winner } => Some(winner),
+            TeamOutcome::Draw => None,
+        }
+    }
+}
+
+/// How one team member did
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemberResult {
+    pub monster: Uuid,
+    pub side: TeamSide,
+    pub remaining_hp: i16,
+    pub fainted: bool,
+    /// Opponents it knocked out
+    pub knockouts: u32,
+    pub damage_dealt: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct TeamBattleResult {
+    pub outcome: TeamOutcome,
+    pub turns: u32,
+    pub events: Vec<BattleEvent>,
+    pub log: Vec<BattleLine>,
+    /// Team A's members in order, then team B's
+    pub members: Vec<MemberResult>,
+    pub seed: [u8; 32],
+    pub rules_hash: [u8; 32],
+}
+
+impl TeamBattleResult {
+    pub fn winner(&self) -> Option<TeamSide> {
+        self.outcome.winner()
+    }
+}
+
+/// Fight team `a` against team `b`, leads first. A day seed mixes in every
+/// member's ID, so swapping anyone out changes the fight.
+pub fn team_battle_with(a: &[Monster], b: &[Monster], config: &BattleConfig) -> TeamBattleResult {
+    team_battle_with_strategies(a, b, config, [&mut AutoStrategy, &mut AutoStrategy])
+}
+
+/// [`team_battle_with`], with `strategies` deciding for `a` and `b` in that
+/// order
+pub fn team_battle_with_strategies(
+    a: &[Monster],
+    b: &[Monster],
+    config: &BattleConfig,
+    strategies: [&mut dyn BattleStrategy; 2],
+) -> TeamBattleResult {
+    let mut state = BattleState::new_team(a, b, config);
+    state.play_out(strategies);
+    state.into_team_result()
+}
+
+impl BattleState {
+    /// Score a team battle. Meant for once the battle [is
+    /// finished](Self::is_finished); before that it's scored as if time ran
+    /// out now.
+    pub fn into_team_result(mut self) -> TeamBattleResult {
+        let outcome = decide(self.sides());
+        let turns = self.turn();
+        self.push_event(BattleEvent::TeamOutcome { outcome, turns });
+        let log = format_log(self.events(), &self.monsters());
+
+        let members = self
+            .sides()
+            .iter()
+            .zip([TeamSide::A, TeamSide::B])
+            .flat_map(|(s, side)| s.roster.iter().map(move |f| (f, side)))
+            .map(|(f, side)| {
+                let id = f.mon.id;
+                let mut result = MemberResult {
+                    monster: id,
+                    side,
+                    remaining_hp: f.hp.max(0),
+                    fainted: f.hp <= 0,
+                    knockouts: 0,
+                    damage_dealt: 0,
+                };
+                for event in self.events() {
+                    if let BattleEvent::Damage {
+                        attacker,
+                        hp_before,
+                        hp_after,
+                        ..
+                    } = event
+                    {
+                        if *attacker == id {
+                            result.damage_dealt += (hp_before - hp_after) as u32;
+                            result.knockouts += u32::from(*hp_after == 0);
+                        }
+                    }
+                }
+                result
+            })
+            .collect();
+
+        TeamBattleResult {
+            outcome,
+            turns,
+            members,
+            seed: self.seed(),
+            rules_hash: self.rules().hash(),
+            log,
+            events: self.into_events(),
+        }
+    }
+}
+
+fn decide(sides: &[Side; 2]) -> TeamOutcome {
+    let score = |s: &Side| {
+        let standing = s.roster.iter().filter(|f| f.hp > 0).count();
+        let hp: i32 = s.roster.iter().map(|f| i32::from(f.hp.max(0))).sum();
+        (standing, hp)
+    };
+    let (a, b) = (score(&sides[0]), score(&sides[1]));
+    match (a.0 == 0, b.0 == 0) {
+        (true, true) => TeamOutcome::Draw,
+        (true, false) => TeamOutcome::Knockout {
+            winner: TeamSide::B,
+        },
+        (false, true) => TeamOutcome::Knockout {
+            winner: TeamSide::A,
+        },
+        (false, false) if a == b => TeamOutcome::Draw,
+        (false, false) => TeamOutcome::Points {
+            winner: if a > b { TeamSide::A } else { TeamSide::B },
+        },
+    }
+}
+
+/// Stands in for a team's ID when seeding: every member ID, in order
+pub(super) fn team_key(team: &[Monster]) -> Uuid {
+    let mut hasher = blake3::Hasher::new_derive_key("qrmonsters team key v1");
+    for m in team {
+        hasher.update(m.id.as_bytes());
+    }
+    let hash = hasher.finalize();
+    Uuid::from_bytes(hash.as_bytes()[..16].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_monster, BattleRules};
+
+    fn team(seed: u8) -> Vec<Monster> {
+        (0..TEAM_BATTLE_SIZE as u8)
+            .map(|i| generate_monster([seed + i; 32]))
+            .collect()
+    }
+
+    #[test]
+    fn test_team_battle() {
+        let (a, b) = (team(30), team(40));
+        let config = BattleConfig::on_day(20_000);
+        let res = team_battle_with(&a, &b, &config);
+        assert_eq!(res.members.len(), 2 * TEAM_BATTLE_SIZE);
+        assert_eq!(
+            res.events.last(),
+            Some(&BattleEvent::TeamOutcome {
+                outcome: res.outcome,
+                turns: res.turns
+            })
+        );
+
+        if let TeamOutcome::Knockout { winner } = res.outcome {
+            let loser_fainted = res
+                .members
+                .iter()
+                .filter(|m| m.side != winner)
+                .all(|m| m.fainted);
+            assert!(loser_fainted);
+            // every fainted front monster but the last was replaced
+            let replacements = res
+                .events
+                .iter()
+                .filter(|e| matches!(e, BattleEvent::SwitchIn { forced: true, .. }))
+                .count();
+            let fainted = res.members.iter().filter(|m| m.fainted).count();
+            assert_eq!(replacements, fainted - 1);
+        }
+        let knockouts: u32 = res.members.iter().map(|m| m.knockouts).sum();
+        assert!(knockouts as usize <= res.members.iter().filter(|m| m.fainted).count());
+
+        // reordering a team changes the seed
+        let mut shuffled = a.clone();
+        shuffled.swap(0, 1);
+        assert_ne!(team_battle_with(&shuffled, &b, &config).seed, res.seed);
+    }
+
+    #[test]
+    fn test_switching_out() {
+        let mut a = team(50);
+        let b = team(60);
+        a[0].stats.hp = 4;
+        let config = BattleConfig {
+            rules: BattleRules {
+                switch_below: 100,
+                ..BattleRules::classic()
+            },
+            ..BattleConfig::on_day(20_000)
+        };
+        let res = team_battle_with(&a, &b, &config);
+        assert!(res.events.iter().any(|e| matches!(
+            e,
+            BattleEvent::SwitchIn { forced: false, replacing, .. } if *replacing == a[0].id
+        )));
+        assert!(res.log.iter().any(|l| l.0.contains("switches out")));
+    }
+}
@@ -0,0 +1,232 @@
+use std::ops::RangeInclusive;
+
+use crate::gen::{archetype_bias, element_nudge, rarity_points, BASE_STATS, CRIT_CAP, LUCK_CAP};
+use crate::{Archetype, Element, Monster, Rarity, Stats, ValidateError};
+
+/// The stat budget `generate_monster` works within for a given
+/// rarity/element/archetype.
+///
+/// The generator starts from a fixed floor, spends one point per allocation
+/// loop on a random stat, and the archetype bias adds a fixed number of
+/// bonus points to HP/ATK/DEF/SPD each loop. A stat line is only possible if
+/// some loop count allowed by the rarity explains it exactly.
+///
+/// Monsters with a rarity, element or archetype from a newer build can't be
+/// checked exactly, so they only get the floor and the widest total this
+/// build's generator could ever reach.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatBudget {
+    pub rarity: Rarity,
+    pub element: Element,
+    pub archetype: Archetype,
+}
+
+impl StatBudget {
+    pub fn new(rarity: Rarity, element: Element, archetype: Archetype) -> Self {
+        StatBudget {
+            rarity,
+            element,
+            archetype,
+        }
+    }
+
+    pub fn for_monster(mon: &Monster) -> Self {
+        StatBudget::new(mon.rarity, mon.element, mon.archetype)
+    }
+
+    /// Points the generator may roll for this rarity
+    pub fn points(&self) -> RangeInclusive<i16> {
+        rarity_points(self.rarity)
+    }
+
+    /// Lowest value each stat can have: the base floor plus the element nudge
+    pub fn minimums(&self) -> Stats {
+        let n = element_nudge(self.element);
+        let b = BASE_STATS;
+        Stats {
+            hp: b.hp + n.hp,
+            atk: b.atk + n.atk,
+            def: b.def + n.def,
+            spd: b.spd + n.spd,
+            crit: (b.crit + n.crit).min(CRIT_CAP),
+            luck: (b.luck + n.luck).min(LUCK_CAP),
+        }
+    }
+
+    /// Smallest and largest possible sum of all six stats
+    pub fn total_range(&self) -> RangeInclusive<i16> {
+        let loops = self.loops();
+        let floor = total(&self.minimums());
+        if !self.is_known() {
+            return floor..=floor + *loops.end() * (1 + MAX_BIAS_PER_LOOP);
+        }
+        let per_loop = 1 + self.bias_per_loop();
+        let lo = floor + *loops.start() * per_loop - self.luck_overflow(*loops.start());
+        let hi = floor + *loops.end() * per_loop;
+        lo..=hi
+    }
+
+    /// Check that `stats` could have come out of the generator
+    pub fn check(&self, stats: &Stats) -> Result<(), ValidateError> {
+        let min = self.minimums();
+        for (stat, value, floor) in [
+            ("hp", stats.hp, min.hp),
+            ("atk", stats.atk, min.atk),
+            ("def", stats.def, min.def),
+            ("spd", stats.spd, min.spd),
+            ("crit", stats.crit, min.crit),
+            ("luck", stats.luck, min.luck),
+        ] {
+            if value < floor {
+                return Err(ValidateError::BelowMinimum {
+                    stat,
+                    value,
+                    min: floor,
+                });
+            }
+        }
+
+        let allowed = self.total_range();
+        let sum = total(stats);
+        if !allowed.contains(&sum) {
+            return Err(ValidateError::TotalOutOfBudget {
+                rarity: self.rarity,
+                total: sum,
+                allowed,
+            });
+        }
+
+        if self.is_known() && !self.loops().any(|n| self.explains(stats, n)) {
+            return Err(ValidateError::ImpossibleDistribution {
+                rarity: self.rarity,
+            });
+        }
+        Ok(())
+    }
+
+    /// Whether this build's generator knows every field, so stats can be
+    /// checked exactly
+    fn is_known(&self) -> bool {
+        !matches!(self.rarity, Rarity::Unknown(_))
+            && !matches!(self.element, Element::Unknown(_))
+            && !matches!(self.archetype, Archetype::Unknown(_))
+    }
+
+    /// Allocation loops the generator runs for this rarity
+    fn loops(&self) -> RangeInclusive<i16> {
+        let base = total(&BASE_STATS);
+        let points = self.points();
+        (*points.start() - base).max(0)..=(*points.end() - base).max(0)
+    }
+
+    fn bias_per_loop(&self) -> i16 {
+        archetype_bias(self.archetype)
+            .iter()
+            .filter(|b| **b)
+            .count() as i16
+    }
+
+    /// Points lost to the luck cap if every loop had landed on luck
+    fn luck_overflow(&self, loops: i16) -> i16 {
+        (self.minimums().luck + loops - LUCK_CAP).max(0)
+    }
+
+    /// Whether exactly `loops` allocation loops can produce `stats`.
+    ///
+    /// Bias points only ever land on HP/ATK/DEF/SPD, so those four must hold
+    /// at least `bias * loops` extra points. The rest of the loops went to
+    /// crit and luck, which are exact unless they sit at their cap.
+    fn explains(&self, stats: &Stats, loops: i16) -> bool {
+        let min = self.minimums();
+        let core = (stats.hp - min.hp)
+            + (stats.atk - min.atk)
+            + (stats.def - min.def)
+            + (stats.spd - min.spd);
+        let bias = self.bias_per_loop() * loops;
+        if core < bias || core > bias + loops {
+            return false;
+        }
+
+        // loops spent on crit + luck
+        let rest = loops - (core - bias);
+        let span = |value: i16, floor: i16, cap: i16| {
+            let spent = value - floor;
+            if value == cap {
+                spent..=loops
+            } else {
+                spent..=spent
+            }
+        };
+        let crit = span(stats.crit, min.crit, CRIT_CAP);
+        let luck = span(stats.luck, min.luck, LUCK_CAP);
+        (crit.start() + luck.start()..=crit.end() + luck.end()).contains(&rest)
+    }
+}
+
+/// One bonus point per HP/ATK/DEF/SPD slot
+const MAX_BIAS_PER_LOOP: i16 = 4;
+
+fn total(s: &Stats) -> i16 {
+    s.hp + s.atk + s.def + s.spd + s.crit + s.luck
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_monster;
+
+    #[test]
+    fn test_generated_monsters_fit_budget() {
+        for i in 0u32..5000 {
+            let mut seed = [0u8; 32];
+            seed[..4].copy_from_slice(&i.to_le_bytes());
+            let mon = generate_monster(seed);
+            assert_eq!(
+                StatBudget::for_monster(&mon).check(&mon.stats),
+                Ok(()),
+                "seed {i}: {mon:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_forged_stats_rejected() {
+        let mut mon = generate_monster([1u8; 32]);
+        mon.rarity = Rarity::Common;
+        mon.stats.atk = 999;
+        assert!(matches!(
+            mon.validate(),
+            Err(ValidateError::TotalOutOfBudget { .. })
+        ));
+
+        // plausible total, but every extra point sits in crit, which the
+        // Tank bias can never produce
+        let budget = StatBudget::new(Rarity::Rare, Element::Fire, Archetype::Tank);
+        let mut stats = budget.minimums();
+        stats.crit += 8;
+        assert!(budget.total_range().contains(&total(&stats)));
+        assert_eq!(
+            budget.check(&stats),
+            Err(ValidateError::ImpossibleDistribution {
+                rarity: Rarity::Rare
+            })
+        );
+    }
+
+    #[test]
+    fn test_unknown_variants_get_loose_budget() {
+        let budget = StatBudget::new(Rarity::Unknown(4), Element::Unknown(5), Archetype::Tank);
+        assert_eq!(budget.points(), 28..=80);
+
+        // the Tank spread above is fine when we can't know the element's rules
+        let mut stats = budget.minimums();
+        stats.crit += 8;
+        assert_eq!(budget.check(&stats), Ok(()));
+
+        stats.hp = 999;
+        assert!(matches!(
+            budget.check(&stats),
+            Err(ValidateError::TotalOutOfBudget { .. })
+        ));
+    }
+}
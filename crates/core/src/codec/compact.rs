@@ -0,0 +1,196 @@
+//! Bit packing and the base-44 text alphabet used by compact share codes.
+//!
+//! Base-44 works like RFC 9285 base45 (two bytes become three characters,
+//! a trailing byte becomes two) but drops the space character, so every
+//! code is in the QR alphanumeric set and survives being trimmed or
+//! word-wrapped in a chat app.
+//!
+//! Checksummed text adds a Luhn mod 44 check character after every
+//! [`GROUP`] characters. That catches any single mistyped character and most
+//! swapped neighbours, and tells us which group the damage is in.
+
+use super::CodecError;
+
+pub(super) const ALPHABET: &[u8; 44] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ$%*+-./:";
+const BASE: u32 = ALPHABET.len() as u32;
+
+/// Characters covered by each check character
+const GROUP: usize = 8;
+
+pub(super) fn base44_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(2) * 3);
+    for pair in bytes.chunks(2) {
+        let (mut n, digits) = match pair {
+            [a, b] => (u32::from(*a) << 8 | u32::from(*b), 3),
+            [a] => (u32::from(*a), 2),
+            _ => unreachable!(),
+        };
+        for _ in 0..digits {
+            out.push(ALPHABET[(n % BASE) as usize] as char);
+            n /= BASE;
+        }
+    }
+    out
+}
+
+/// Decode base-44 text. Lowercase is accepted so codes can be typed by hand,
+/// and ASCII whitespace is skipped so wrapped codes still paste.
+///
+/// `offset` is added to character positions in errors, so callers can report
+/// them relative to the whole share code.
+pub(super) fn base44_decode(text: &str, offset: usize) -> Result<Vec<u8>, CodecError> {
+    let digits: Vec<u32> = digits(text, offset)?.into_iter().map(|(_, d)| d).collect();
+
+    let mut out = Vec::with_capacity(digits.len() / 3 * 2 + 1);
+    for group in digits.chunks(3) {
+        let n = group.iter().rev().fold(0u32, |n, d| n * BASE + d);
+        match group.len() {
+            3 if n <= 0xFFFF => out.extend_from_slice(&(n as u16).to_be_bytes()),
+            2 if n <= 0xFF => out.push(n as u8),
+            _ => return Err(CodecError::Malformed("bad base-44 group")),
+        }
+    }
+    Ok(out)
+}
+
+/// Insert a check character after every [`GROUP`] characters of base-44 text
+pub(super) fn add_checks(text: &str) -> String {
+    let digits: Vec<u32> = text.chars().filter_map(digit).collect();
+    let mut out = String::with_capacity(text.len() + text.len().div_ceil(GROUP));
+    for group in digits.chunks(GROUP) {
+        out.extend(group.iter().map(|&d| ALPHABET[d as usize] as char));
+        out.push(ALPHABET[luhn_check(group) as usize] as char);
+    }
+    out
+}
+
+/// Verify and remove check characters. Whitespace is skipped like in
+/// [`base44_decode`].
+///
+/// The first group that fails is reported as a [`CodecError::Corrupted`]
+/// region, with positions offset by `offset` like [`base44_decode`]. The
+/// region is in the text as given, so it spans any whitespace inside it.
+pub(super) fn strip_checks(text: &str, offset: usize) -> Result<String, CodecError> {
+    let digits = digits(text, offset)?;
+
+    let mut out = String::with_capacity(text.len());
+    for group in digits.chunks(GROUP + 1) {
+        let (data, check) = group.split_at(group.len() - 1);
+        let data: Vec<u32> = data.iter().map(|&(_, d)| d).collect();
+        if data.is_empty() || luhn_check(&data) != check[0].1 {
+            return Err(CodecError::Corrupted {
+                region: Some(group[0].0..check[0].0 + 1),
+            });
+        }
+        out.extend(data.iter().map(|&d| ALPHABET[d as usize] as char));
+    }
+    Ok(out)
+}
+
+/// Base-44 digits of `text` with their positions, skipping ASCII whitespace
+fn digits(text: &str, offset: usize) -> Result<Vec<(usize, u32)>, CodecError> {
+    let mut digits = Vec::with_capacity(text.len());
+    for (i, ch) in text.chars().enumerate() {
+        if ch.is_ascii_whitespace() {
+            continue;
+        }
+        let position = offset + i;
+        let digit = digit(ch).ok_or(CodecError::BadCharacter { ch, position })?;
+        digits.push((position, digit));
+    }
+    Ok(digits)
+}
+
+/// Luhn mod N check digit for base-44 digits
+fn luhn_check(digits: &[u32]) -> u32 {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            let d = if i % 2 == 0 { d * 2 } else { d };
+            d / BASE + d % BASE
+        })
+        .sum();
+    (BASE - sum % BASE) % BASE
+}
+
+fn digit(ch: char) -> Option<u32> {
+    let upper = ch.to_ascii_uppercase();
+    ALPHABET
+        .iter()
+        .position(|&a| a as char == upper)
+        .map(|d| d as u32)
+}
+
+/// Appends values MSB-first, `bits` at a time
+#[derive(Default)]
+pub(super) struct BitWriter {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BitWriter {
+    pub fn write(&mut self, value: u32, bits: u32) {
+        debug_assert!(
+            bits == 32 || value >> bits == 0,
+            "{value} overflows {bits} bits"
+        );
+        for i in (0..bits).rev() {
+            if self.len.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            if value >> i & 1 == 1 {
+                *self.bytes.last_mut().unwrap() |= 0x80 >> (self.len % 8);
+            }
+            self.len += 1;
+        }
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Reads values written by [`BitWriter`]
+pub(super) struct BitReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        BitReader { bytes, pos: 0 }
+    }
+
+    pub fn read(&mut self, bits: u32) -> Result<u32, CodecError> {
+        let mut value = 0u32;
+        for _ in 0..bits {
+            let byte = self
+                .bytes
+                .get(self.pos / 8)
+                .ok_or(CodecError::Malformed("truncated"))?;
+            let bit = byte >> (7 - self.pos % 8) & 1;
+            value = value << 1 | u32::from(bit);
+            self.pos += 1;
+        }
+        Ok(value)
+    }
+
+    /// Error unless only zero padding is left
+    pub fn finish(self) -> Result<(), CodecError> {
+        let used = self.pos.div_ceil(8);
+        let padding = self.bytes.get(self.pos / 8).map_or(0, |b| {
+            if self.pos.is_multiple_of(8) {
+                0
+            } else {
+                b & (0xFF >> (self.pos % 8))
+            }
+        });
+        if self.bytes.len() > used || padding != 0 {
+            Err(CodecError::Malformed("trailing data"))
+        } else {
+            Ok(())
+        }
+    }
+}
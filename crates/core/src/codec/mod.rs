@@ -0,0 +1,648 @@
+use std::fmt;
+use std::ops::Range;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use bincode::Options as _;
+
+use crate::{Monster, RulesError, ValidateError};
+
+mod compact;
+mod replay;
+mod signed;
+mod team;
+mod v1;
+mod v2;
+mod words;
+
+use compact::{add_checks, base44_decode, base44_encode, strip_checks};
+pub use replay::*;
+pub use signed::*;
+pub use team::*;
+use v1::MonsterV1;
+pub use words::*;
+
+/// Every share code starts with `QRM<version>:`
+const PREFIX_TAG: &str = "QRM";
+
+/// Bytes of payload checksum appended to `QRM3` payloads
+const CHECKSUM_LEN: usize = 2;
+
+/// Longest input we'll try to decode. Real codes are a few hundred
+/// characters at most; anything longer is rejected before any parsing.
+pub const MAX_SHARE_LEN: usize = 1024;
+
+/// Share-code layouts this build knows how to read.
+///
+/// New codes are always written with [`ShareVersion::CURRENT`]; older layouts
+/// are decoded into their frozen struct and migrated into the current
+/// [`Monster`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareVersion {
+    /// `QRM1:` bincode of the original monster struct, URL-safe base64
+    V1,
+    /// `QRM2:` bit-packed monster, base-44 text in the QR alphanumeric set
+    V2,
+    /// `QRM3:` the `QRM2` payload plus a checksum, with a check character
+    /// after every few characters of text so typos can be located
+    V3,
+}
+
+impl ShareVersion {
+    pub const CURRENT: ShareVersion = ShareVersion::V3;
+
+    pub fn number(self) -> u8 {
+        match self {
+            ShareVersion::V1 => 1,
+            ShareVersion::V2 => 2,
+            ShareVersion::V3 => 3,
+        }
+    }
+
+    pub fn from_number(n: u8) -> Option<Self> {
+        match n {
+            1 => Some(ShareVersion::V1),
+            2 => Some(ShareVersion::V2),
+            3 => Some(ShareVersion::V3),
+            _ => None,
+        }
+    }
+
+    pub fn prefix(self) -> String {
+        format!("{PREFIX_TAG}{}:", self.number())
+    }
+}
+
+/// Error produced while encoding or decoding a share code
+#[derive(Debug)]
+pub enum CodecError {
+    /// The input is longer than [`MAX_SHARE_LEN`]
+    TooLong { len: usize, max: usize },
+    /// The input does not start with a `QRM<n>:` prefix
+    MissingPrefix,
+    /// The code was written by a newer build using a layout we don't know
+    UnsupportedVersion(u8),
+    /// The payload's own version field disagrees with its prefix
+    VersionMismatch { prefix: u8, payload: u8 },
+    /// The body after the prefix is not valid URL-safe base64
+    Base64(base64::DecodeError),
+    /// A character outside the base-44 alphabet, at this position in the code
+    BadCharacter { ch: char, position: usize },
+    /// A packed payload is cut short, has leftovers, or holds an impossible value
+    Malformed(&'static str),
+    /// The monster could not be serialized
+    Serialize(bincode::Error),
+    /// The decoded bytes are not a valid monster layout
+    Deserialize(bincode::Error),
+    /// The monster decoded fine but failed validation
+    Invalid(ValidateError),
+    /// A signed code's signature doesn't match its monster and trainer
+    BadSignature,
+    /// A checksum failed, so the code was mistyped or damaged in transit.
+    /// `region` is the range of character positions that failed, if known.
+    Corrupted { region: Option<Range<usize>> },
+    /// A team code with no members or more than [`MAX_TEAM_SIZE`]
+    TeamSize { count: usize, max: usize },
+    /// A word-list code contains a word that isn't in the list, at this word index
+    UnknownWord { word: String, position: usize },
+    /// A replay code's battle rules are outside what the engine allows
+    InvalidRules(RulesError),
+    /// A signing trainer's name breaks the same policy as monster names
+    InvalidTrainer(ValidateError),
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodecError::TooLong { len, max } => {
+                write!(f, "share code too long: {len} characters (max {max})")
+            }
+            CodecError::MissingPrefix => write!(f, "missing {PREFIX_TAG}<n>: prefix"),
+            CodecError::UnsupportedVersion(n) => {
+                write!(f, "unsupported share code version {PREFIX_TAG}{n}")
+            }
+            CodecError::VersionMismatch { prefix, payload } => write!(
+                f,
+                "share code prefix says version {prefix} but payload says {payload}"
+            ),
+            CodecError::Base64(e) => write!(f, "invalid base64: {e}"),
+            CodecError::BadCharacter { ch, position } => {
+                write!(f, "invalid character {ch:?} at position {position}")
+            }
+            CodecError::Malformed(what) => write!(f, "malformed payload: {what}"),
+            CodecError::Serialize(e) => write!(f, "could not serialize monster: {e}"),
+            CodecError::Deserialize(e) => write!(f, "could not deserialize monster: {e}"),
+            CodecError::Invalid(e) => write!(f, "invalid monster: {e}"),
+            CodecError::BadSignature => write!(f, "signature does not match"),
+            CodecError::Corrupted { region: Some(r) } => {
+                write!(f, "share code damaged at positions {}..{}", r.start, r.end)
+            }
+            CodecError::Corrupted { region: None } => write!(f, "share code checksum mismatch"),
+            CodecError::TeamSize { count, max } => {
+                write!(f, "team has {count} members (must be 1 to {max})")
+            }
+            CodecError::UnknownWord { word, position } => {
+                write!(f, "unknown word {word:?} at word {position}")
+            }
+            CodecError::InvalidRules(e) => write!(f, "invalid battle rules: {e}"),
+            CodecError::InvalidTrainer(e) => write!(f, "invalid trainer: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CodecError::TooLong { .. }
+            | CodecError::MissingPrefix
+            | CodecError::UnsupportedVersion(_)
+            | CodecError::VersionMismatch { .. }
+            | CodecError::BadCharacter { .. }
+            | CodecError::Malformed(_)
+            | CodecError::BadSignature
+            | CodecError::Corrupted { .. }
+            | CodecError::TeamSize { .. }
+            | CodecError::UnknownWord { .. } => None,
+            CodecError::Base64(e) => Some(e),
+            CodecError::Serialize(e) | CodecError::Deserialize(e) => Some(e),
+            CodecError::Invalid(e) | CodecError::InvalidTrainer(e) => Some(e),
+            CodecError::InvalidRules(e) => Some(e),
+        }
+    }
+}
+
+impl From<ValidateError> for CodecError {
+    fn from(e: ValidateError) -> Self {
+        CodecError::Invalid(e)
+    }
+}
+
+pub fn encode_share(mon: &Monster) -> Result<String, CodecError> {
+    let (version, bytes) = encode_payload(mon)?;
+    let body = match version {
+        ShareVersion::V1 => URL_SAFE_NO_PAD.encode(bytes),
+        ShareVersion::V2 => base44_encode(&bytes),
+        ShareVersion::V3 => add_checks(&base44_encode(&bytes)),
+    };
+    Ok(format!("{}{body}", version.prefix()))
+}
+
+/// Decode a plain or signed share code.
+///
+/// Signed codes are verified, but the signer is dropped; use
+/// [`decode_share_verified`] to find out who signed it.
+pub fn decode_share(s: &str) -> Result<Monster, CodecError> {
+    check_len(s)?;
+    if s.starts_with(SIGNED_PREFIX) {
+        return decode_share_verified(s).map(|shared| shared.monster);
+    }
+    let (version, body) = split_prefix(s)?;
+    let bytes = match version {
+        ShareVersion::V1 => URL_SAFE_NO_PAD.decode(body).map_err(CodecError::Base64)?,
+        ShareVersion::V2 => base44_decode(body, s.len() - body.len())?,
+        ShareVersion::V3 => {
+            let offset = s.len() - body.len();
+            base44_decode(&strip_checks(body, offset)?, offset)?
+        }
+    };
+    decode_payload(version, &bytes)
+}
+
+/// Serialize `mon` in the current layout
+fn encode_payload(mon: &Monster) -> Result<(ShareVersion, Vec<u8>), CodecError> {
+    mon.validate()?;
+    let version = ShareVersion::CURRENT;
+    let bytes = match version {
+        ShareVersion::V1 => {
+            bincode::serialize(&MonsterV1::from(mon)).map_err(CodecError::Serialize)?
+        }
+        ShareVersion::V2 => v2::pack(mon),
+        ShareVersion::V3 => {
+            let mut bytes = v2::pack(mon);
+            bytes.extend_from_slice(&checksum(&bytes));
+            bytes
+        }
+    };
+    Ok((version, bytes))
+}
+
+/// Deserialize a `version` layout, migrate it and validate the result
+fn decode_payload(version: ShareVersion, bytes: &[u8]) -> Result<Monster, CodecError> {
+    let mon = match version {
+        ShareVersion::V1 => {
+            let old: MonsterV1 = untrusted()
+                .deserialize(bytes)
+                .map_err(CodecError::Deserialize)?;
+            check_payload_version(version, old.v)?;
+            Monster::from(old)
+        }
+        ShareVersion::V2 => v2::unpack(bytes)?,
+        ShareVersion::V3 => {
+            let split = bytes
+                .len()
+                .checked_sub(CHECKSUM_LEN)
+                .ok_or(CodecError::Malformed("truncated"))?;
+            let (packed, sum) = bytes.split_at(split);
+            if sum != checksum(packed) {
+                return Err(CodecError::Corrupted { region: None });
+            }
+            v2::unpack(packed)?
+        }
+    };
+    mon.validate()?;
+    Ok(mon)
+}
+
+/// Checksum over a packed payload, catching damage the check characters miss
+fn checksum(bytes: &[u8]) -> [u8; CHECKSUM_LEN] {
+    let hash = blake3::hash(bytes);
+    let mut sum = [0u8; CHECKSUM_LEN];
+    sum.copy_from_slice(&hash.as_bytes()[..CHECKSUM_LEN]);
+    sum
+}
+
+fn check_len(s: &str) -> Result<(), CodecError> {
+    if s.len() > MAX_SHARE_LEN {
+        Err(CodecError::TooLong {
+            len: s.len(),
+            max: MAX_SHARE_LEN,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// bincode settings for untrusted input.
+///
+/// Same wire format as `bincode::serialize`, but length prefixes can't ask
+/// for more than a share code could possibly hold, and bytes left over after
+/// the value are an error instead of being silently ignored.
+fn untrusted() -> impl bincode::Options {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_limit(MAX_SHARE_LEN as u64)
+        .reject_trailing_bytes()
+}
+
+/// Split `QRM<n>:<body>` into its layout version and body
+fn split_prefix(s: &str) -> Result<(ShareVersion, &str), CodecError> {
+    let rest = s
+        .strip_prefix(PREFIX_TAG)
+        .ok_or(CodecError::MissingPrefix)?;
+    let (num, body) = rest.split_once(':').ok_or(CodecError::MissingPrefix)?;
+    if num.is_empty() || !num.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(CodecError::MissingPrefix);
+    }
+    let n: u8 = num.parse().map_err(|_| CodecError::MissingPrefix)?;
+    let version = ShareVersion::from_number(n).ok_or(CodecError::UnsupportedVersion(n))?;
+    Ok((version, body))
+}
+
+fn check_payload_version(version: ShareVersion, payload: u8) -> Result<(), CodecError> {
+    if payload == version.number() {
+        Ok(())
+    } else {
+        Err(CodecError::VersionMismatch {
+            prefix: version.number(),
+            payload,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_monster, Archetype, Element, Rarity, Stats, MAX_ENUM_INDEX};
+    use proptest::prelude::*;
+    use uuid::Uuid;
+
+    /// Golden share codes, one per historical layout
+    const FIXTURES: &str = include_str!("../../fixtures/share_codes.txt");
+
+    fn fixture(version: &str) -> &'static str {
+        FIXTURES
+            .lines()
+            .filter(|l| !l.trim().is_empty() && !l.starts_with('#'))
+            .find(|l| l.starts_with(version))
+            .expect("fixture present")
+    }
+
+    /// The first `QRM1` golden code, written by hand before stats were
+    /// checked against the rarity budget. Kept to pin how it's rejected.
+    const HANDCRAFTED_QRM1: &str = "QRM1:ARAAAAAAAAAAWmt8jZ4PShuMLT5PWmt8jQsAAAAAAAAATW9zc3kgR29sZW0BAAAAAgAAAAAAAAAYAAsADQAIAAQABQA";
+
+    fn golden_monster() -> Monster {
+        Monster {
+            v: Monster::VERSION,
+            id: Uuid::from_u128(0x5a6b_7c8d_9e0f_4a1b_8c2d_3e4f_5a6b_7c8d),
+            name: "Mossy Golem".into(),
+            rarity: Rarity::Rare,
+            element: Element::Earth,
+            archetype: Archetype::Tank,
+            stats: Stats {
+                hp: 21,
+                atk: 10,
+                def: 10,
+                spd: 7,
+                crit: 3,
+                luck: 4,
+            },
+            provenance: None,
+        }
+    }
+
+    #[test]
+    fn test_share_round_trip() {
+        let mut mon = generate_monster([7u8; 32]);
+        mon.provenance = None;
+        let code = encode_share(&mon).unwrap();
+        assert!(code.starts_with(&ShareVersion::CURRENT.prefix()));
+        assert_eq!(decode_share(&code).unwrap(), mon);
+    }
+
+    #[test]
+    fn test_golden_qrm1_decodes() {
+        let mut mon = generate_monster([1; 32]);
+        mon.provenance = None;
+        assert_eq!(decode_share(fixture("QRM1:")).unwrap(), mon);
+    }
+
+    #[test]
+    fn test_handcrafted_qrm1_rejected() {
+        // its spread is too lopsided for a Rare, which Import explains
+        assert!(matches!(
+            decode_share(HANDCRAFTED_QRM1),
+            Err(CodecError::Invalid(ValidateError::ImpossibleDistribution {
+                rarity: Rarity::Rare
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_golden_qrm2_decodes() {
+        let code = fixture("QRM2:");
+        assert_eq!(decode_share(code).unwrap(), golden_monster());
+        assert_eq!(
+            decode_share(&format!("QRM2:{}", code[5..].to_lowercase())).unwrap(),
+            golden_monster()
+        );
+    }
+
+    #[test]
+    fn test_golden_qrm3_decodes() {
+        let code = fixture("QRM3:");
+        assert_eq!(decode_share(code).unwrap(), golden_monster());
+        assert_eq!(encode_share(&golden_monster()).unwrap(), code);
+        assert_eq!(
+            decode_share(&format!("QRM3:{}", code[5..].to_lowercase())).unwrap(),
+            golden_monster()
+        );
+    }
+
+    #[test]
+    fn test_every_typo_is_located() {
+        let code = encode_share(&golden_monster()).unwrap();
+        let prefix = ShareVersion::CURRENT.prefix().len();
+        for (i, original) in code.char_indices().skip(prefix) {
+            for typo in compact::ALPHABET.iter().map(|&b| b as char) {
+                if typo == original {
+                    continue;
+                }
+                let mut bad = code.clone();
+                bad.replace_range(i..i + 1, &typo.to_string());
+                match decode_share(&bad) {
+                    Err(CodecError::Corrupted { region: Some(r) }) => {
+                        assert!(r.contains(&i), "{bad}: {r:?} misses {i}")
+                    }
+                    other => panic!("{bad}: {other:?}"),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lost_characters_are_detected() {
+        let code = encode_share(&golden_monster()).unwrap();
+        for i in 5..code.len() {
+            let mut bad = code.clone();
+            bad.remove(i);
+            // the shift usually trips the damaged group's check, but may
+            // only show up in a later group or the checksum
+            assert!(
+                matches!(
+                    decode_share(&bad),
+                    Err(CodecError::Corrupted { .. } | CodecError::Malformed(_))
+                ),
+                "{bad}"
+            );
+        }
+
+        // a swap the check characters miss is still caught by the checksum
+        let packed = v2::pack(&golden_monster());
+        let mut bytes = packed.clone();
+        bytes.extend_from_slice(&checksum(&packed));
+        bytes[3] ^= 1;
+        assert!(matches!(
+            decode_payload(ShareVersion::V3, &bytes),
+            Err(CodecError::Corrupted { region: None })
+        ));
+    }
+
+    #[test]
+    fn test_wrapped_code_decodes() {
+        let code = encode_share(&golden_monster()).unwrap();
+        let mid = code.len() / 2;
+        let wrapped = format!("{}\n{}", &code[..mid], &code[mid..]);
+        assert_eq!(decode_share(&wrapped).unwrap(), golden_monster());
+        assert_eq!(
+            decode_share(&wrapped.replace('\n', " \r\n ")).unwrap(),
+            golden_monster()
+        );
+
+        // typos past the break are still located in the text as pasted
+        let last = wrapped.len() - 1;
+        let mut bad = wrapped.clone();
+        let typo = if bad.ends_with('0') { "1" } else { "0" };
+        bad.replace_range(last.., typo);
+        match decode_share(&bad) {
+            Err(CodecError::Corrupted { region: Some(r) }) => assert!(r.contains(&last), "{r:?}"),
+            other => panic!("{other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_compact_code_is_smaller_and_alphanumeric() {
+        let mon = golden_monster();
+        let v1 = format!(
+            "QRM1:{}",
+            URL_SAFE_NO_PAD.encode(bincode::serialize(&MonsterV1::from(&mon)).unwrap())
+        );
+        let v2 = format!("QRM2:{}", base44_encode(&v2::pack(&mon)));
+        assert!(v2.len() * 2 < v1.len(), "{v2} vs {v1}");
+        let current = encode_share(&mon).unwrap();
+        assert!(current.len() < v1.len());
+        assert!(current
+            .chars()
+            .all(|c| c.is_ascii_digit() || c.is_ascii_uppercase() || "$%*+-./:".contains(c)));
+
+        // names outside the generator tables still round-trip
+        let mut custom = mon.clone();
+        custom.name = "Sir Fluff-o'War".into();
+        assert_eq!(
+            decode_share(&encode_share(&custom).unwrap()).unwrap(),
+            custom
+        );
+    }
+
+    #[test]
+    fn test_unknown_variants_survive_codecs() {
+        let mut mon = golden_monster();
+        mon.element = Element::Unknown(7);
+        mon.archetype = Archetype::Unknown(4);
+        assert_eq!(decode_share(&encode_share(&mon).unwrap()).unwrap(), mon);
+        assert_eq!(decode_words(&encode_words(&mon).unwrap()).unwrap(), mon);
+
+        // an old-layout code from a build that appended a sixth element
+        let code = format!(
+            "QRM1:{}",
+            URL_SAFE_NO_PAD.encode(bincode::serialize(&MonsterV1::from(&mon)).unwrap())
+        );
+        assert_eq!(decode_share(&code).unwrap(), mon);
+
+        mon.element = Element::Unknown(MAX_ENUM_INDEX + 1);
+        assert!(matches!(
+            encode_share(&mon),
+            Err(CodecError::Invalid(ValidateError::IndexOutOfRange {
+                field: "element",
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_decode_error_variants() {
+        assert!(matches!(
+            decode_share("nope"),
+            Err(CodecError::MissingPrefix)
+        ));
+        assert!(matches!(
+            decode_share("QRM:AAAA"),
+            Err(CodecError::MissingPrefix)
+        ));
+        assert!(matches!(
+            decode_share("QRM9:AAAA"),
+            Err(CodecError::UnsupportedVersion(9))
+        ));
+        assert!(matches!(
+            decode_share("QRM1:!!"),
+            Err(CodecError::Base64(_))
+        ));
+        assert!(matches!(
+            decode_share("QRM1:AAAA"),
+            Err(CodecError::Deserialize(_))
+        ));
+
+        let mut old = MonsterV1::from(&golden_monster());
+        old.stats.luck = 99;
+        let code = format!(
+            "QRM1:{}",
+            URL_SAFE_NO_PAD.encode(bincode::serialize(&old).unwrap())
+        );
+        assert!(matches!(
+            decode_share(&code),
+            Err(CodecError::Invalid(ValidateError::StatOutOfRange {
+                stat: "luck",
+                value: 99,
+                ..
+            }))
+        ));
+
+        old.stats.luck = 4;
+        old.v = 3;
+        let code = format!(
+            "QRM1:{}",
+            URL_SAFE_NO_PAD.encode(bincode::serialize(&old).unwrap())
+        );
+        assert!(matches!(
+            decode_share(&code),
+            Err(CodecError::VersionMismatch {
+                prefix: 1,
+                payload: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn test_hostile_payloads_rejected() {
+        let long = format!("QRM1:{}", "A".repeat(MAX_SHARE_LEN));
+        assert!(matches!(
+            decode_share(&long),
+            Err(CodecError::TooLong { .. })
+        ));
+
+        // name length prefix claiming an exabyte
+        let mut bytes = bincode::serialize(&MonsterV1::from(&golden_monster())).unwrap();
+        bytes[25..33].copy_from_slice(&(1u64 << 60).to_le_bytes());
+        let code = format!("QRM1:{}", URL_SAFE_NO_PAD.encode(&bytes));
+        assert!(matches!(
+            decode_share(&code),
+            Err(CodecError::Deserialize(_))
+        ));
+
+        let mut bytes = bincode::serialize(&MonsterV1::from(&golden_monster())).unwrap();
+        bytes.push(0);
+        let code = format!("QRM1:{}", URL_SAFE_NO_PAD.encode(&bytes));
+        assert!(matches!(
+            decode_share(&code),
+            Err(CodecError::Deserialize(_))
+        ));
+
+        let mut old = MonsterV1::from(&golden_monster());
+        old.name = "<script>".into();
+        let code = format!(
+            "QRM1:{}",
+            URL_SAFE_NO_PAD.encode(bincode::serialize(&old).unwrap())
+        );
+        assert!(matches!(
+            decode_share(&code),
+            Err(CodecError::Invalid(ValidateError::NameCharacter('<')))
+        ));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_any_text_never_panics(s in "\\PC{0,1200}") {
+            let _ = decode_share(&s);
+            let _ = decode_share_verified(&s);
+            let _ = decode_words(&s);
+        }
+
+        #[test]
+        fn prop_any_payload_never_panics(bytes in prop::collection::vec(any::<u8>(), 0..700)) {
+            let body = URL_SAFE_NO_PAD.encode(&bytes);
+            for prefix in ["QRM1:", "QRMS:"] {
+                let _ = decode_share_verified(&format!("{prefix}{body}"));
+            }
+        }
+
+        #[test]
+        fn prop_any_compact_payload_never_panics(bytes in prop::collection::vec(any::<u8>(), 0..600)) {
+            let _ = decode_share(&format!("QRM2:{}", base44_encode(&bytes)));
+            let _ = decode_share(&format!("QRM3:{}", add_checks(&base44_encode(&bytes))));
+            let _ = decode_team(&format!("{TEAM_PREFIX}{}", add_checks(&base44_encode(&bytes))));
+        }
+
+        #[test]
+        fn prop_corrupted_code_never_panics(
+            idx in any::<prop::sample::Index>(),
+            byte in any::<u8>(),
+        ) {
+            let mut bytes = bincode::serialize(&MonsterV1::from(&golden_monster())).unwrap();
+            let i = idx.index(bytes.len());
+            bytes[i] = byte;
+            let code = format!("QRM1:{}", URL_SAFE_NO_PAD.encode(&bytes));
+            if let Ok(mon) = decode_share(&code) {
+                prop_assert!(mon.validate().is_ok());
+            }
+        }
+    }
+}
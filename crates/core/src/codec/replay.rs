@@ -0,0 +1,148 @@
+//! `QRMB:` battle replay codes: a [`BattleReplay`] as text, for sharing a
+//! fight so anyone can check it with [`crate::verify_replay`].
+//!
+//! bincode of the record in URL-safe base64, with both monsters packed as
+//! share-code payloads so they're validated on the way in like any import.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use bincode::Options as _;
+use serde::{Deserialize, Serialize};
+
+use super::{decode_payload, encode_payload, CodecError, ShareVersion};
+use crate::{Action, BattleReplay, BattleRules};
+
+pub const REPLAY_PREFIX: &str = "QRMB:";
+
+/// Longest replay code we'll try to decode. Replays carry every action, so
+/// they run well past a share code.
+pub const MAX_REPLAY_LEN: usize = 16 * 1024;
+
+/// [`BattleReplay`] as it goes over the wire
+#[derive(Serialize, Deserialize)]
+struct ReplayWire {
+    version: u8,
+    /// [`ShareVersion`] number of the monster payloads
+    layout: u8,
+    a: Vec<u8>,
+    b: Vec<u8>,
+    rules: BattleRules,
+    rules_hash: [u8; 32],
+    seed: [u8; 32],
+    actions: Vec<Action>,
+    hash: [u8; 32],
+}
+
+pub fn encode_replay(replay: &BattleReplay) -> Result<String, CodecError> {
+    let (layout, a) = encode_payload(&replay.a)?;
+    let (_, b) = encode_payload(&replay.b)?;
+    let wire = ReplayWire {
+        version: replay.version,
+        layout: layout.number(),
+        a,
+        b,
+        rules: replay.rules.clone(),
+        rules_hash: replay.rules_hash,
+        seed: replay.seed,
+        actions: replay.actions.clone(),
+        hash: replay.hash,
+    };
+    let bytes = bincode::serialize(&wire).map_err(CodecError::Serialize)?;
+    Ok(format!("{REPLAY_PREFIX}{}", URL_SAFE_NO_PAD.encode(bytes)))
+}
+
+/// Decode a `QRMB:` code. Both monsters and the rules are validated; the
+/// battle itself isn't checked until [`crate::verify_replay`].
+pub fn decode_replay(s: &str) -> Result<BattleReplay, CodecError> {
+    if s.len() > MAX_REPLAY_LEN {
+        return Err(CodecError::TooLong {
+            len: s.len(),
+            max: MAX_REPLAY_LEN,
+        });
+    }
+    let body = s
+        .strip_prefix(REPLAY_PREFIX)
+        .ok_or(CodecError::MissingPrefix)?;
+    let bytes = URL_SAFE_NO_PAD.decode(body).map_err(CodecError::Base64)?;
+    let wire: ReplayWire = bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_limit(MAX_REPLAY_LEN as u64)
+        .reject_trailing_bytes()
+        .deserialize(&bytes)
+        .map_err(CodecError::Deserialize)?;
+
+    let layout = ShareVersion::from_number(wire.layout)
+        .ok_or(CodecError::UnsupportedVersion(wire.layout))?;
+    // the hash travels with the rules, so only validation keeps them sane
+    wire.rules.validate().map_err(CodecError::InvalidRules)?;
+    Ok(BattleReplay {
+        version: wire.version,
+        a: decode_payload(layout, &wire.a)?,
+        b: decode_payload(layout, &wire.b)?,
+        rules: wire.rules,
+        rules_hash: wire.rules_hash,
+        seed: wire.seed,
+        actions: wire.actions,
+        hash: wire.hash,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{battle_with, generate_monster, verify_replay, BattleConfig, ReplayError};
+
+    #[test]
+    fn test_replay_code_round_trip() {
+        let a = generate_monster([41; 32]);
+        let b = generate_monster([42; 32]);
+        let config = BattleConfig {
+            rules: BattleRules::standard(),
+            ..BattleConfig::on_day(20_000)
+        };
+        let res = battle_with(&a, &b, &config);
+        let replay = BattleReplay::new(&a, &b, &config.rules, &res);
+
+        let code = encode_replay(&replay).unwrap();
+        assert!(code.starts_with(REPLAY_PREFIX));
+        let decoded = decode_replay(&code).unwrap();
+        // share payloads don't carry provenance
+        assert_eq!(decoded.actions, replay.actions);
+        assert_eq!((decoded.a.id, decoded.b.id), (a.id, b.id));
+        assert_eq!(verify_replay(&decoded).unwrap().outcome, res.outcome);
+
+        assert!(matches!(
+            decode_replay(&code[1..]),
+            Err(CodecError::MissingPrefix)
+        ));
+        assert!(matches!(
+            decode_replay(&code[..code.len() - 4]),
+            Err(CodecError::Deserialize(_) | CodecError::Base64(_))
+        ));
+    }
+
+    #[test]
+    fn test_crafted_rules_rejected() {
+        let a = generate_monster([41; 32]);
+        let b = generate_monster([42; 32]);
+        let config = BattleConfig {
+            rules: BattleRules::standard(),
+            ..BattleConfig::on_day(20_000)
+        };
+        let res = battle_with(&a, &b, &config);
+        let mut crafted = BattleReplay::new(&a, &b, &config.rules, &res);
+        // these used to overflow the engine; the hash is easy to redo
+        crafted.rules.crit_multiplier = i16::MAX;
+        crafted.rules.die_sides = i16::MAX;
+        crafted.rules_hash = crafted.rules.hash();
+
+        let code = encode_replay(&crafted).unwrap();
+        match decode_replay(&code) {
+            Err(CodecError::InvalidRules(e)) => assert_eq!(e.rule, "die_sides"),
+            other => panic!("{other:?}"),
+        }
+        assert!(matches!(
+            verify_replay(&crafted),
+            Err(ReplayError::InvalidRules(_))
+        ));
+    }
+}
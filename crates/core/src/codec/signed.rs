@@ -0,0 +1,242 @@
+//! `QRMS:` signed share codes.
+//!
+//! The monster is serialized exactly like a plain code, then wrapped together
+//! with the originating trainer's name and ed25519 public key and signed with
+//! their secret key. Decoding verifies the signature before the monster is
+//! migrated and validated.
+
+use std::fmt;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use bincode::Options as _;
+use ed25519_dalek::{Signature, Signer as _, SigningKey, Verifier as _, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use super::{check_len, decode_payload, encode_payload, untrusted, CodecError, ShareVersion};
+use crate::monster::check_name;
+use crate::Monster;
+
+pub(super) const SIGNED_PREFIX: &str = "QRMS:";
+
+/// Domain separator so share signatures can't be replayed elsewhere
+const SIGNING_CONTEXT: &str = "qrmonsters signed share v1";
+
+/// Public identity of the trainer who signed a share code
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Trainer {
+    /// Self-chosen display name, covered by the signature
+    pub name: String,
+    /// ed25519 public key
+    pub public_key: [u8; 32],
+}
+
+impl Trainer {
+    /// Short hex fingerprint of the public key, for telling trainers apart
+    pub fn fingerprint(&self) -> String {
+        blake3::hash(&self.public_key).as_bytes()[..4]
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+}
+
+/// A trainer's signing identity. Keep this local; only [`Trainer`] is shared.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TrainerKeypair {
+    pub name: String,
+    secret: [u8; 32],
+}
+
+impl fmt::Debug for TrainerKeypair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrainerKeypair")
+            .field("name", &self.name)
+            .field("public_key", &self.trainer().public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TrainerKeypair {
+    /// Create a fresh keypair from the OS random number generator. `name`
+    /// has to follow the monster name policy.
+    pub fn generate(name: impl Into<String>) -> Result<Self, CodecError> {
+        let key = SigningKey::generate(&mut rand::rngs::OsRng);
+        Self::from_secret(name, key.to_bytes())
+    }
+
+    pub fn from_secret(name: impl Into<String>, secret: [u8; 32]) -> Result<Self, CodecError> {
+        let name = name.into();
+        check_name(&name).map_err(CodecError::InvalidTrainer)?;
+        Ok(TrainerKeypair { name, secret })
+    }
+
+    pub fn trainer(&self) -> Trainer {
+        Trainer {
+            name: self.name.clone(),
+            public_key: self.signing_key().verifying_key().to_bytes(),
+        }
+    }
+
+    fn signing_key(&self) -> SigningKey {
+        SigningKey::from_bytes(&self.secret)
+    }
+}
+
+/// A decoded share code plus who vouched for it, if anyone
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedMonster {
+    pub monster: Monster,
+    /// `Some` only for signed codes whose signature verified
+    pub signer: Option<Trainer>,
+}
+
+/// Wire envelope for `QRMS:` codes
+#[derive(Serialize, Deserialize)]
+struct SignedShare {
+    /// Layout of `payload`, same numbering as `QRM<n>:`
+    version: u8,
+    payload: Vec<u8>,
+    trainer: String,
+    public_key: [u8; 32],
+    signature: Vec<u8>,
+}
+
+/// Encode `mon` as a `QRMS:` code signed by `keys`
+pub fn encode_signed_share(mon: &Monster, keys: &TrainerKeypair) -> Result<String, CodecError> {
+    // `name` is public, so it may have changed since the keys were made
+    check_name(&keys.name).map_err(CodecError::InvalidTrainer)?;
+    let (version, payload) = encode_payload(mon)?;
+    let message = signed_message(version.number(), &payload, &keys.name)?;
+    let signature = keys.signing_key().sign(&message);
+    let envelope = SignedShare {
+        version: version.number(),
+        payload,
+        trainer: keys.name.clone(),
+        public_key: keys.trainer().public_key,
+        signature: signature.to_bytes().to_vec(),
+    };
+    let bytes = bincode::serialize(&envelope).map_err(CodecError::Serialize)?;
+    Ok(format!("{SIGNED_PREFIX}{}", URL_SAFE_NO_PAD.encode(bytes)))
+}
+
+/// Decode a plain or signed share code, verifying and reporting the signer
+pub fn decode_share_verified(s: &str) -> Result<SharedMonster, CodecError> {
+    check_len(s)?;
+    let Some(body) = s.strip_prefix(SIGNED_PREFIX) else {
+        return super::decode_share(s).map(|monster| SharedMonster {
+            monster,
+            signer: None,
+        });
+    };
+
+    let bytes = URL_SAFE_NO_PAD.decode(body).map_err(CodecError::Base64)?;
+    let envelope: SignedShare = untrusted()
+        .deserialize(&bytes)
+        .map_err(CodecError::Deserialize)?;
+
+    let key =
+        VerifyingKey::from_bytes(&envelope.public_key).map_err(|_| CodecError::BadSignature)?;
+    let signature =
+        Signature::from_slice(&envelope.signature).map_err(|_| CodecError::BadSignature)?;
+    let message = signed_message(envelope.version, &envelope.payload, &envelope.trainer)?;
+    key.verify(&message, &signature)
+        .map_err(|_| CodecError::BadSignature)?;
+    // a valid signature only means the signer chose this name, so it gets
+    // the same checks as a monster's before it's shown anywhere
+    check_name(&envelope.trainer).map_err(CodecError::InvalidTrainer)?;
+
+    let version = ShareVersion::from_number(envelope.version)
+        .ok_or(CodecError::UnsupportedVersion(envelope.version))?;
+    let monster = decode_payload(version, &envelope.payload)?;
+    Ok(SharedMonster {
+        monster,
+        signer: Some(Trainer {
+            name: envelope.trainer,
+            public_key: envelope.public_key,
+        }),
+    })
+}
+
+/// Bytes covered by the signature: context, layout version, payload, trainer name
+fn signed_message(version: u8, payload: &[u8], trainer: &str) -> Result<Vec<u8>, CodecError> {
+    bincode::serialize(&(SIGNING_CONTEXT, version, payload, trainer)).map_err(CodecError::Serialize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_monster, ValidateError};
+
+    #[test]
+    fn test_signed_round_trip_and_tamper() {
+        let keys = TrainerKeypair::from_secret("Ash", [9u8; 32]).unwrap();
+        let mut mon = generate_monster([3u8; 32]);
+        mon.provenance = None;
+
+        let code = encode_signed_share(&mon, &keys).unwrap();
+        let shared = decode_share_verified(&code).unwrap();
+        assert_eq!(shared.monster, mon);
+        assert_eq!(shared.signer, Some(keys.trainer()));
+        assert_eq!(crate::decode_share(&code).unwrap(), mon);
+
+        // swap the trainer name without re-signing
+        let bytes = URL_SAFE_NO_PAD
+            .decode(&code[SIGNED_PREFIX.len()..])
+            .unwrap();
+        let mut envelope: SignedShare = bincode::deserialize(&bytes).unwrap();
+        envelope.trainer = "Gary".into();
+        let forged = format!(
+            "{SIGNED_PREFIX}{}",
+            URL_SAFE_NO_PAD.encode(bincode::serialize(&envelope).unwrap())
+        );
+        assert!(matches!(
+            decode_share_verified(&forged),
+            Err(CodecError::BadSignature)
+        ));
+
+        let plain = crate::encode_share(&mon).unwrap();
+        assert_eq!(decode_share_verified(&plain).unwrap().signer, None);
+    }
+
+    #[test]
+    fn test_trainer_name_policy() {
+        let bidi = "Ash\u{202e}yraG";
+        assert!(matches!(
+            TrainerKeypair::from_secret(bidi, [9u8; 32]),
+            Err(CodecError::InvalidTrainer(ValidateError::NameCharacter(
+                '\u{202e}'
+            )))
+        ));
+        assert!(matches!(
+            TrainerKeypair::generate("x".repeat(100)),
+            Err(CodecError::InvalidTrainer(ValidateError::NameLength { .. }))
+        ));
+
+        let mut keys = TrainerKeypair::from_secret("Ash", [9u8; 32]).unwrap();
+        let mon = generate_monster([3u8; 32]);
+        keys.name = bidi.into();
+        assert!(matches!(
+            encode_signed_share(&mon, &keys),
+            Err(CodecError::InvalidTrainer(_))
+        ));
+
+        // a properly signed code still can't smuggle the name in
+        let (version, payload) = encode_payload(&mon).unwrap();
+        let message = signed_message(version.number(), &payload, bidi).unwrap();
+        let envelope = SignedShare {
+            version: version.number(),
+            payload,
+            trainer: bidi.into(),
+            public_key: keys.trainer().public_key,
+            signature: keys.signing_key().sign(&message).to_bytes().to_vec(),
+        };
+        let crafted = format!(
+            "{SIGNED_PREFIX}{}",
+            URL_SAFE_NO_PAD.encode(bincode::serialize(&envelope).unwrap())
+        );
+        assert!(matches!(
+            decode_share_verified(&crafted),
+            Err(CodecError::InvalidTrainer(ValidateError::NameCharacter(_)))
+        ));
+    }
+}
@@ -0,0 +1,200 @@
+//! `QRMT:` team bundles: an ordered list of monsters plus an optional name.
+//!
+//! Byte layout, then checksummed base-44 text like `QRM3:`:
+//!
+//! | field          | bytes                                  |
+//! |----------------|----------------------------------------|
+//! | member layout  | 1, a [`ShareVersion`] number           |
+//! | name length    | 1, zero for no name                    |
+//! | name           | ASCII                                  |
+//! | member count   | 1                                      |
+//! | members        | 1 length byte, then that member's payload |
+//! | checksum       | 2                                      |
+
+use super::compact::{add_checks, base44_decode, base44_encode, strip_checks};
+use super::{
+    check_len, checksum, decode_payload, encode_payload, CodecError, ShareVersion, CHECKSUM_LEN,
+};
+use crate::monster::check_name;
+use crate::Monster;
+
+pub const TEAM_PREFIX: &str = "QRMT:";
+
+/// Most monsters a team code can carry
+pub const MAX_TEAM_SIZE: usize = 6;
+
+/// Monsters shared together, in the order their trainer picked
+#[derive(Debug, Clone, PartialEq)]
+pub struct Team {
+    /// Follows the same rules as monster names
+    pub name: Option<String>,
+    pub members: Vec<Monster>,
+}
+
+pub fn encode_team(team: &Team) -> Result<String, CodecError> {
+    check_team_size(team.members.len())?;
+    let mut bytes = vec![ShareVersion::CURRENT.number()];
+    match &team.name {
+        Some(name) => {
+            check_name(name)?;
+            bytes.push(name.len() as u8);
+            bytes.extend_from_slice(name.as_bytes());
+        }
+        None => bytes.push(0),
+    }
+
+    bytes.push(team.members.len() as u8);
+    for mon in &team.members {
+        let (_, payload) = encode_payload(mon)?;
+        let len =
+            u8::try_from(payload.len()).map_err(|_| CodecError::Malformed("member too long"))?;
+        bytes.push(len);
+        bytes.extend_from_slice(&payload);
+    }
+
+    let sum = checksum(&bytes);
+    bytes.extend_from_slice(&sum);
+    Ok(format!(
+        "{TEAM_PREFIX}{}",
+        add_checks(&base44_encode(&bytes))
+    ))
+}
+
+/// Decode a `QRMT:` code. Every member is validated like a single share code.
+pub fn decode_team(s: &str) -> Result<Team, CodecError> {
+    check_len(s)?;
+    let body = s
+        .strip_prefix(TEAM_PREFIX)
+        .ok_or(CodecError::MissingPrefix)?;
+    let offset = TEAM_PREFIX.len();
+    let bytes = base44_decode(&strip_checks(body, offset)?, offset)?;
+
+    let split = bytes
+        .len()
+        .checked_sub(CHECKSUM_LEN)
+        .ok_or(CodecError::Malformed("truncated"))?;
+    let (mut rest, sum) = bytes.split_at(split);
+    if sum != checksum(rest) {
+        return Err(CodecError::Corrupted { region: None });
+    }
+
+    let layout = take(&mut rest, 1)?[0];
+    let version =
+        ShareVersion::from_number(layout).ok_or(CodecError::UnsupportedVersion(layout))?;
+
+    let name_len = take(&mut rest, 1)?[0] as usize;
+    let name = match name_len {
+        0 => None,
+        n => {
+            let name = std::str::from_utf8(take(&mut rest, n)?)
+                .map_err(|_| CodecError::Malformed("team name"))?;
+            check_name(name)?;
+            Some(name.to_string())
+        }
+    };
+
+    let count = take(&mut rest, 1)?[0] as usize;
+    check_team_size(count)?;
+    let mut members = Vec::with_capacity(count);
+    for _ in 0..count {
+        let len = take(&mut rest, 1)?[0] as usize;
+        members.push(decode_payload(version, take(&mut rest, len)?)?);
+    }
+    if !rest.is_empty() {
+        return Err(CodecError::Malformed("trailing data"));
+    }
+    Ok(Team { name, members })
+}
+
+fn check_team_size(count: usize) -> Result<(), CodecError> {
+    if (1..=MAX_TEAM_SIZE).contains(&count) {
+        Ok(())
+    } else {
+        Err(CodecError::TeamSize {
+            count,
+            max: MAX_TEAM_SIZE,
+        })
+    }
+}
+
+/// Split `n` bytes off the front of `bytes`
+fn take<'a>(bytes: &mut &'a [u8], n: usize) -> Result<&'a [u8], CodecError> {
+    if bytes.len() < n {
+        return Err(CodecError::Malformed("truncated"));
+    }
+    let (head, tail) = bytes.split_at(n);
+    *bytes = tail;
+    Ok(head)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode_share, generate_monster, ValidateError};
+
+    fn team(n: u8) -> Team {
+        Team {
+            name: Some("Moss Squad".into()),
+            members: (0..n)
+                .map(|i| {
+                    let mut mon = generate_monster([i; 32]);
+                    mon.provenance = None;
+                    mon
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_team_round_trip() {
+        let team = team(MAX_TEAM_SIZE as u8);
+        let code = encode_team(&team).unwrap();
+        assert!(code.starts_with(TEAM_PREFIX));
+        assert_eq!(decode_team(&code).unwrap(), team);
+        assert_eq!(
+            decode_team(&code.to_lowercase().replacen("qrmt", "QRMT", 1)).unwrap(),
+            team
+        );
+
+        let unnamed = Team {
+            name: None,
+            ..team.clone()
+        };
+        assert_eq!(
+            decode_team(&encode_team(&unnamed).unwrap()).unwrap(),
+            unnamed
+        );
+
+        // a team code is not a single monster
+        assert!(decode_share(&code).is_err());
+    }
+
+    #[test]
+    fn test_team_rejects_bad_input() {
+        assert!(matches!(
+            encode_team(&team(0)),
+            Err(CodecError::TeamSize { count: 0, .. })
+        ));
+        assert!(matches!(
+            encode_team(&team(MAX_TEAM_SIZE as u8 + 1)),
+            Err(CodecError::TeamSize { .. })
+        ));
+
+        let mut named = team(1);
+        named.name = Some("<b>".into());
+        assert!(matches!(
+            encode_team(&named),
+            Err(CodecError::Invalid(ValidateError::NameCharacter('<')))
+        ));
+
+        let code = encode_team(&team(2)).unwrap();
+        let mut bad = code.clone();
+        let i = code.len() / 2;
+        let swap = if &code[i..=i] == "A" { "B" } else { "A" };
+        bad.replace_range(i..=i, swap);
+        assert!(matches!(
+            decode_team(&bad),
+            Err(CodecError::Corrupted { region: Some(r) }) if r.contains(&i)
+        ));
+    }
+}
@@ -0,0 +1,49 @@
+//! The `QRM1:` layout: plain bincode of the original monster struct.
+//!
+//! This struct is frozen. Printed QR codes depend on its exact field order
+//! and types, so changes to [`Monster`] must never be mirrored here.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{Archetype, Element, Monster, Rarity, Stats};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct MonsterV1 {
+    pub v: u8,
+    pub id: Uuid,
+    pub name: String,
+    pub rarity: Rarity,
+    pub element: Element,
+    pub archetype: Archetype,
+    pub stats: Stats,
+}
+
+impl From<&Monster> for MonsterV1 {
+    fn from(mon: &Monster) -> Self {
+        MonsterV1 {
+            v: 1,
+            id: mon.id,
+            name: mon.name.clone(),
+            rarity: mon.rarity,
+            element: mon.element,
+            archetype: mon.archetype,
+            stats: mon.stats.clone(),
+        }
+    }
+}
+
+impl From<MonsterV1> for Monster {
+    fn from(old: MonsterV1) -> Self {
+        Monster {
+            v: Monster::VERSION,
+            id: old.id,
+            name: old.name,
+            rarity: old.rarity,
+            element: old.element,
+            archetype: old.archetype,
+            stats: old.stats,
+            provenance: None,
+        }
+    }
+}
@@ -0,0 +1,126 @@
+//! The `QRM2:` layout: a bit-packed monster in base-44 text.
+//!
+//! | field      | bits                                        |
+//! |------------|---------------------------------------------|
+//! | id         | 128                                         |
+//! | rarity     | 4                                           |
+//! | element    | 4                                           |
+//! | archetype  | 4                                           |
+//! | hp/atk/def/spd | 10 each                                 |
+//! | crit       | 6                                           |
+//! | luck       | 5                                           |
+//! | name       | 1 flag, then 4+4 table indices, or 5-bit length + 7-bit ASCII |
+//!
+//! Enums get 4 bits so new variants fit without a new layout; indexes this
+//! build doesn't know decode as `Unknown`. Like [`super::v1`], this layout is
+//! frozen once released.
+
+use uuid::Uuid;
+
+use super::compact::{BitReader, BitWriter};
+use super::CodecError;
+use crate::gen::{ADJECTIVES, NOUNS};
+use crate::{Archetype, Element, Monster, Rarity, Stats};
+
+const ENUM_BITS: u32 = 4;
+const STAT_BITS: u32 = 10;
+const CRIT_BITS: u32 = 6;
+const LUCK_BITS: u32 = 5;
+const NAME_LEN_BITS: u32 = 5;
+const CHAR_BITS: u32 = 7;
+const NAME_INDEX_BITS: u32 = 4;
+
+// appending past this would need a new layout
+const _: () = assert!(
+    ADJECTIVES.len() <= 1 << NAME_INDEX_BITS && NOUNS.len() <= 1 << NAME_INDEX_BITS,
+    "name tables outgrew the QRM2 index fields"
+);
+
+/// Pack a monster that has already passed [`Monster::validate`]
+pub(super) fn pack(mon: &Monster) -> Vec<u8> {
+    let mut w = BitWriter::default();
+    for b in mon.id.as_bytes() {
+        w.write(u32::from(*b), 8);
+    }
+    w.write(u32::from(mon.rarity.index()), ENUM_BITS);
+    w.write(u32::from(mon.element.index()), ENUM_BITS);
+    w.write(u32::from(mon.archetype.index()), ENUM_BITS);
+
+    let s = &mon.stats;
+    for stat in [s.hp, s.atk, s.def, s.spd] {
+        w.write(stat as u32, STAT_BITS);
+    }
+    w.write(s.crit as u32, CRIT_BITS);
+    w.write(s.luck as u32, LUCK_BITS);
+
+    match table_name(&mon.name) {
+        Some((adj, noun)) => {
+            w.write(1, 1);
+            w.write(adj, NAME_INDEX_BITS);
+            w.write(noun, NAME_INDEX_BITS);
+        }
+        None => {
+            w.write(0, 1);
+            w.write(mon.name.len() as u32, NAME_LEN_BITS);
+            for b in mon.name.bytes() {
+                w.write(u32::from(b), CHAR_BITS);
+            }
+        }
+    }
+    w.finish()
+}
+
+/// Unpack a `QRM2` payload. The caller still has to validate the result.
+pub(super) fn unpack(bytes: &[u8]) -> Result<Monster, CodecError> {
+    let mut r = BitReader::new(bytes);
+    let mut id = [0u8; 16];
+    for b in &mut id {
+        *b = r.read(8)? as u8;
+    }
+    let rarity = Rarity::from_index(r.read(ENUM_BITS)? as u8);
+    let element = Element::from_index(r.read(ENUM_BITS)? as u8);
+    let archetype = Archetype::from_index(r.read(ENUM_BITS)? as u8);
+
+    let stats = Stats {
+        hp: r.read(STAT_BITS)? as i16,
+        atk: r.read(STAT_BITS)? as i16,
+        def: r.read(STAT_BITS)? as i16,
+        spd: r.read(STAT_BITS)? as i16,
+        crit: r.read(CRIT_BITS)? as i16,
+        luck: r.read(LUCK_BITS)? as i16,
+    };
+
+    let name = if r.read(1)? == 1 {
+        let adj = ADJECTIVES.get(r.read(NAME_INDEX_BITS)? as usize);
+        let noun = NOUNS.get(r.read(NAME_INDEX_BITS)? as usize);
+        match (adj, noun) {
+            (Some(adj), Some(noun)) => format!("{adj} {noun}"),
+            _ => return Err(CodecError::Malformed("unknown name index")),
+        }
+    } else {
+        let len = r.read(NAME_LEN_BITS)?;
+        (0..len)
+            .map(|_| r.read(CHAR_BITS).map(|c| c as u8 as char))
+            .collect::<Result<String, _>>()?
+    };
+    r.finish()?;
+
+    Ok(Monster {
+        v: Monster::VERSION,
+        id: Uuid::from_bytes(id),
+        name,
+        rarity,
+        element,
+        archetype,
+        stats,
+        provenance: None,
+    })
+}
+
+/// Table indices if `name` is exactly "<adjective> <noun>" from the generator
+fn table_name(name: &str) -> Option<(u32, u32)> {
+    let (adj, noun) = name.split_once(' ')?;
+    let adj = ADJECTIVES.iter().position(|a| *a == adj)?;
+    let noun = NOUNS.iter().position(|n| *n == noun)?;
+    Some((adj as u32, noun as u32))
+}
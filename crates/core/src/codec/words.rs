@@ -0,0 +1,119 @@
+//! Word-list share codes, for reading a monster out loud.
+//!
+//! The `QRM2` bit-packed payload is spelled one word per byte from
+//! [`WORDS`], followed by a checksum word. Like the other layouts, the word
+//! list and its order are frozen once released.
+
+use super::{check_len, checksum, decode_payload, v2, CodecError, ShareVersion};
+use crate::Monster;
+
+/// Spell `mon` as space-separated words
+pub fn encode_words(mon: &Monster) -> Result<String, CodecError> {
+    mon.validate()?;
+    let bytes = v2::pack(mon);
+    let sum = checksum(&bytes)[0];
+    let words: Vec<&str> = bytes
+        .iter()
+        .chain(std::iter::once(&sum))
+        .map(|&b| WORDS[b as usize])
+        .collect();
+    Ok(words.join(" "))
+}
+
+/// Decode words written by [`encode_words`], validating like [`super::decode_share`].
+///
+/// Case is ignored, and words may be separated by spaces, commas or dashes.
+pub fn decode_words(s: &str) -> Result<Monster, CodecError> {
+    check_len(s)?;
+    let mut bytes = Vec::new();
+    for (position, word) in s
+        .split(|c: char| c.is_whitespace() || c == ',' || c == '-')
+        .filter(|w| !w.is_empty())
+        .enumerate()
+    {
+        let index = WORDS
+            .binary_search(&word.to_ascii_lowercase().as_str())
+            .map_err(|_| CodecError::UnknownWord {
+                word: word.to_string(),
+                position,
+            })?;
+        bytes.push(index as u8);
+    }
+
+    let sum = bytes.pop().ok_or(CodecError::Malformed("no words"))?;
+    if sum != checksum(&bytes)[0] {
+        return Err(CodecError::Corrupted { region: None });
+    }
+    decode_payload(ShareVersion::V2, &bytes)
+}
+
+/// One word per byte value, sorted so lookups can binary search
+const WORDS: [&str; 256] = [
+    "acorn", "actor", "adult", "agent", "alarm", "album", "alley", "amber", "angle", "apple",
+    "apron", "arena", "arrow", "atlas", "attic", "autumn", "bacon", "badge", "bagel", "baker",
+    "bamboo", "banjo", "barn", "basket", "beach", "beard", "beaver", "bell", "berry", "bison",
+    "blanket", "blossom", "boat", "bonfire", "border", "bottle", "bridge", "brook", "bubble",
+    "bucket", "buffalo", "bundle", "button", "cabin", "cactus", "camel", "candle", "canoe",
+    "canyon", "carpet", "carrot", "castle", "cattle", "cedar", "cellar", "cherry", "chess",
+    "chimney", "cider", "circus", "citrus", "clover", "cobalt", "cocoa", "comet", "copper",
+    "coral", "cotton", "cougar", "cowboy", "coyote", "crayon", "cricket", "crystal", "cupcake",
+    "curtain", "dagger", "daisy", "dancer", "delta", "desert", "diamond", "dinner", "doctor",
+    "dolphin", "donkey", "dragon", "drum", "eagle", "earth", "easel", "echo", "eclipse", "elbow",
+    "ember", "emerald", "engine", "falcon", "farmer", "feather", "fence", "ferry", "fiddle",
+    "finch", "flame", "flute", "forest", "fossil", "fountain", "fox", "galaxy", "garden", "garlic",
+    "gecko", "ginger", "giraffe", "glacier", "glove", "goblin", "gold", "gorilla", "grape",
+    "guitar", "hammer", "harbor", "harvest", "hazel", "helmet", "hockey", "honey", "hornet",
+    "husky", "igloo", "island", "ivory", "jacket", "jaguar", "jelly", "jester", "jungle", "kayak",
+    "kettle", "kitten", "koala", "ladder", "lagoon", "lantern", "lemon", "letter", "lizard",
+    "lobster", "locket", "lotus", "magnet", "mango", "maple", "marble", "meadow", "melon",
+    "mermaid", "meteor", "mitten", "monkey", "mosaic", "mustard", "napkin", "nectar", "needle",
+    "nickel", "noodle", "oasis", "ocean", "octopus", "olive", "onion", "orange", "orbit", "orchid",
+    "otter", "oyster", "paddle", "panda", "panther", "parrot", "peanut", "pebble", "pepper",
+    "piano", "pickle", "pigeon", "pillow", "pirate", "planet", "plum", "pocket", "pony", "potato",
+    "puffin", "pumpkin", "puppet", "quartz", "quill", "rabbit", "radar", "radish", "raven",
+    "ribbon", "river", "robin", "rocket", "saddle", "salmon", "sandal", "sapphire", "scarf",
+    "scooter", "seal", "shadow", "shovel", "silver", "sketch", "sloth", "snail", "spider",
+    "sponge", "squid", "summit", "sunset", "swan", "tablet", "tango", "teapot", "thistle",
+    "thunder", "tiger", "tomato", "topaz", "tornado", "tractor", "trumpet", "tulip", "tunnel",
+    "turtle", "umbrella", "unicorn", "valley", "velvet", "violin", "volcano", "waffle", "walnut",
+    "walrus", "wizard", "yogurt", "zebra", "zipper",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_monster;
+
+    #[test]
+    fn test_words_round_trip() {
+        assert!(WORDS.windows(2).all(|w| w[0] < w[1]));
+
+        let mut mon = generate_monster([5u8; 32]);
+        mon.provenance = None;
+        let words = encode_words(&mon).unwrap();
+        assert_eq!(decode_words(&words).unwrap(), mon);
+        assert_eq!(
+            decode_words(&words.to_uppercase().replace(' ', "-")).unwrap(),
+            mon
+        );
+
+        let mut typo = words.split(' ').collect::<Vec<_>>();
+        typo[3] = "bananna";
+        assert!(matches!(
+            decode_words(&typo.join(" ")),
+            Err(CodecError::UnknownWord { position: 3, .. })
+        ));
+
+        // a valid but wrong word is caught by the checksum word
+        typo[3] = if words.split(' ').nth(3) == Some("zipper") {
+            "zebra"
+        } else {
+            "zipper"
+        };
+        assert!(matches!(
+            decode_words(&typo.join(" ")),
+            Err(CodecError::Corrupted { .. })
+        ));
+        assert!(matches!(decode_words(""), Err(CodecError::Malformed(_))));
+    }
+}
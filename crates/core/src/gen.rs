@@ -1,65 +1,78 @@
+use std::ops::RangeInclusive;
+
 use rand::seq::SliceRandom;
 use rand::Rng;
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 use uuid::Uuid;
 
-use crate::{Archetype, Element, Monster, Rarity, Stats};
+use crate::{Archetype, Element, Monster, Provenance, Rarity, Stats};
+
+/// Bumped whenever `generate_monster` would produce a different monster for
+/// the same seed, so stored provenance records stay auditable.
+pub const GENERATOR_VERSION: u16 = 1;
+
+/// Derive a monster's ID from its seed (UUIDv8 over a blake3 of the seed).
+pub fn monster_id(seed: [u8; 32]) -> Uuid {
+    let hash = blake3::derive_key("qrmonsters monster id v1", &seed);
+    let mut buf = [0u8; 16];
+    buf.copy_from_slice(&hash[..16]);
+    Uuid::new_v8(buf)
+}
 
 pub fn generate_monster(seed: [u8; 32]) -> Monster {
     let mut rng = ChaCha8Rng::from_seed(seed);
 
     let rarity = roll_rarity(&mut rng);
-    let element = *[
-        Element::Fire,
-        Element::Water,
-        Element::Earth,
-        Element::Air,
-        Element::Electric,
-    ]
-    .choose(&mut rng)
-    .unwrap();
-    let archetype = *[
-        Archetype::Tank,
-        Archetype::Assassin,
-        Archetype::Mage,
-        Archetype::Beast,
-    ]
-    .choose(&mut rng)
-    .unwrap();
+    let element = *Element::KNOWN.choose(&mut rng).unwrap();
+    let archetype = *Archetype::KNOWN.choose(&mut rng).unwrap();
 
     let name = format!("{} {}", adj(&mut rng), noun(&mut rng));
 
-    let points = match rarity {
-        Rarity::Common => rng.gen_range(28..=40),
-        Rarity::Rare => rng.gen_range(38..=52),
-        Rarity::Epic => rng.gen_range(48..=64),
-        Rarity::Legendary => rng.gen_range(60..=80),
-    };
+    let points = rng.gen_range(rarity_points(rarity));
 
     let mut stats = allocate(points, archetype, &mut rng);
 
     // element flavor (tiny nudge)
-    match element {
-        Element::Fire => stats.atk += 2,
-        Element::Water => stats.def += 2,
-        Element::Earth => stats.hp += 3,
-        Element::Air => stats.spd += 2,
-        Element::Electric => stats.crit += 1,
-    }
+    let nudge = element_nudge(element);
+    stats.hp += nudge.hp;
+    stats.atk += nudge.atk;
+    stats.def += nudge.def;
+    stats.spd += nudge.spd;
+    stats.crit += nudge.crit;
+    stats.luck += nudge.luck;
 
     // clamp
-    stats.crit = stats.crit.clamp(0, 50);
-    stats.luck = stats.luck.clamp(0, 20);
+    stats.crit = stats.crit.clamp(0, CRIT_CAP);
+    stats.luck = stats.luck.clamp(0, LUCK_CAP);
 
     Monster {
-        v: 1,
-        id: Uuid::new_v4(),
+        v: Monster::VERSION,
+        id: monster_id(seed),
         name,
         rarity,
         element,
         archetype,
         stats,
+        provenance: Some(Provenance {
+            seed,
+            generator: GENERATOR_VERSION,
+        }),
+    }
+}
+
+impl Provenance {
+    /// Re-run the generator that produced this record.
+    ///
+    /// Returns `None` if the record came from a different generator version,
+    /// since this build cannot reproduce it.
+    pub fn regenerate(&self) -> Option<Monster> {
+        (self.generator == GENERATOR_VERSION).then(|| generate_monster(self.seed))
+    }
+
+    /// Whether regenerating from this record yields `mon` bit-for-bit.
+    pub fn matches(&self, mon: &Monster) -> bool {
+        self.regenerate().is_some_and(|m| &m == mon)
     }
 }
 
@@ -74,24 +87,78 @@ fn roll_rarity<R: Rng>(rng: &mut R) -> Rarity {
     }
 }
 
+/// Stat floor every monster starts from before points are allocated
+pub(crate) const BASE_STATS: Stats = Stats {
+    hp: 12,
+    atk: 6,
+    def: 6,
+    spd: 6,
+    crit: 3,
+    luck: 3,
+};
+
+pub(crate) const CRIT_CAP: i16 = 50;
+pub(crate) const LUCK_CAP: i16 = 20;
+
+/// Total stat points rolled for a rarity, before archetype bias and element nudge.
+/// Rarities from newer builds get the widest range of any known rarity.
+pub(crate) fn rarity_points(rarity: Rarity) -> RangeInclusive<i16> {
+    match rarity {
+        Rarity::Common => 28..=40,
+        Rarity::Rare => 38..=52,
+        Rarity::Epic => 48..=64,
+        Rarity::Legendary => 60..=80,
+        Rarity::Unknown(_) => 28..=80,
+    }
+}
+
+/// Archetype bias: each allocation loop rolls one of HP/ATK/DEF/SPD per
+/// slot, and slots marked `true` get a bonus point.
+pub(crate) fn archetype_bias(archetype: Archetype) -> [bool; 4] {
+    match archetype {
+        Archetype::Tank => [true, true, true, false],
+        Archetype::Assassin => [false, true, false, true],
+        Archetype::Mage => [false, true, false, false],
+        Archetype::Beast => [true, true, false, false],
+        Archetype::Unknown(_) => [false; 4],
+    }
+}
+
+/// Flat stat bonus each element adds after allocation
+pub(crate) fn element_nudge(element: Element) -> Stats {
+    let mut nudge = Stats {
+        hp: 0,
+        atk: 0,
+        def: 0,
+        spd: 0,
+        crit: 0,
+        luck: 0,
+    };
+    match element {
+        Element::Fire => nudge.atk = 2,
+        Element::Water => nudge.def = 2,
+        Element::Earth => nudge.hp = 3,
+        Element::Air => nudge.spd = 2,
+        Element::Electric => nudge.crit = 1,
+        Element::Unknown(_) => {}
+    }
+    nudge
+}
+
 fn allocate<R: Rng>(points: i16, archetype: Archetype, rng: &mut R) -> Stats {
-    // base minimums
-    let mut hp = 12;
-    let mut atk = 6;
-    let mut def = 6;
-    let mut spd = 6;
-    let mut crit = 3;
-    let mut luck = 3;
+    let Stats {
+        mut hp,
+        mut atk,
+        mut def,
+        mut spd,
+        mut crit,
+        mut luck,
+    } = BASE_STATS;
 
     let mut remaining = points - (hp + atk + def + spd + crit + luck);
 
     // archetype bias: choose which stats get more of the remaining
-    let bias: &[fn(&mut i16)] = match archetype {
-        Archetype::Tank => &[|v| *v += 1, |v| *v += 1, |v| *v += 1, |_v| {}],
-        Archetype::Assassin => &[|_v| {}, |v| *v += 1, |_v| {}, |v| *v += 1],
-        Archetype::Mage => &[|_v| {}, |v| *v += 1, |_v| {}, |_v| {}],
-        Archetype::Beast => &[|v| *v += 1, |v| *v += 1, |_v| {}, |_v| {}],
-    };
+    let bias = archetype_bias(archetype);
 
     while remaining > 0 {
         let roll = rng.gen_range(0..100);
@@ -118,8 +185,11 @@ fn allocate<R: Rng>(points: i16, archetype: Archetype, rng: &mut R) -> Stats {
 
         // tiny bias each loop
         let mut knobs = [0i16, 0, 0, 0];
-        for f in bias {
-            f(&mut knobs[rng.gen_range(0..4)]);
+        for bonus in bias {
+            let knob = rng.gen_range(0..4);
+            if bonus {
+                knobs[knob] += 1;
+            }
         }
         hp += knobs[0];
         atk += knobs[1];
@@ -139,19 +209,42 @@ fn allocate<R: Rng>(points: i16, archetype: Archetype, rng: &mut R) -> Stats {
     }
 }
 
+/// Name tables. Share codes refer to names by index into these, so only ever
+/// append to them, and no further than the 16 entries the codec has room for.
+pub(crate) const ADJECTIVES: [&str; 10] = [
+    "Blazing", "Mossy", "Glacial", "Vicious", "Tiny", "Ancient", "Stormy", "Cursed", "Shiny",
+    "Wicked",
+];
+pub(crate) const NOUNS: [&str; 10] = [
+    "Otter", "Golem", "Wisp", "Crab", "Raptor", "Moth", "Slime", "Wolf", "Sprite", "Toad",
+];
+
 fn adj<R: Rng>(rng: &mut R) -> &'static str {
-    *[
-        "Blazing", "Mossy", "Glacial", "Vicious", "Tiny", "Ancient", "Stormy", "Cursed", "Shiny",
-        "Wicked",
-    ]
-    .choose(rng)
-    .unwrap()
+    ADJECTIVES.choose(rng).unwrap()
 }
 
 fn noun<R: Rng>(rng: &mut R) -> &'static str {
-    *[
-        "Otter", "Golem", "Wisp", "Crab", "Raptor", "Moth", "Slime", "Wolf", "Sprite", "Toad",
-    ]
-    .choose(rng)
-    .unwrap()
+    NOUNS.choose(rng).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_monster() {
+        let a = generate_monster([42u8; 32]);
+        let b = generate_monster([42u8; 32]);
+        assert_eq!(a, b);
+        assert_eq!(a.id, monster_id([42u8; 32]));
+        assert_eq!(a.id.get_version_num(), 8);
+        assert_ne!(a.id, generate_monster([43u8; 32]).id);
+
+        let prov = a.provenance.expect("generated monsters carry provenance");
+        assert!(prov.matches(&a));
+
+        let mut edited = a.clone();
+        edited.stats.atk += 1;
+        assert!(!prov.matches(&edited));
+    }
 }
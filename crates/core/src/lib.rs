@@ -2,8 +2,12 @@ pub mod monster;
 pub mod gen;
 pub mod battle;
 pub mod codec;
+pub mod budget;
+pub mod moves;
 
 pub use monster::*;
 pub use gen::*;
 pub use battle::*;
 pub use codec::*;
+pub use budget::*;
+pub use moves::*;
@@ -1,29 +1,132 @@
-use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::RangeInclusive;
+
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+use crate::StatBudget;
+
+// Rarity, Element and Archetype each keep an `Unknown` variant for values
+// added by newer builds. Their wire index is the declaration order, and new
+// variants must only ever be appended.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Rarity {
     Common,
     Rare,
     Epic,
     Legendary,
+    /// A rarity from a newer build, by wire index
+    Unknown(u8),
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Element {
     Fire,
     Water,
     Earth,
     Air,
     Electric,
+    /// An element from a newer build, by wire index
+    Unknown(u8),
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Archetype {
     Tank,
     Assassin,
     Mage,
     Beast,
+    /// An archetype from a newer build, by wire index
+    Unknown(u8),
+}
+
+/// Largest enum index a monster can carry; share codes pack them in 4 bits
+pub const MAX_ENUM_INDEX: u8 = 15;
+
+impl Rarity {
+    /// Variants this build knows, in wire order
+    pub const KNOWN: [Rarity; 4] = [
+        Rarity::Common,
+        Rarity::Rare,
+        Rarity::Epic,
+        Rarity::Legendary,
+    ];
+
+    pub fn index(self) -> u8 {
+        match self {
+            Rarity::Common => 0,
+            Rarity::Rare => 1,
+            Rarity::Epic => 2,
+            Rarity::Legendary => 3,
+            Rarity::Unknown(i) => i,
+        }
+    }
+
+    pub fn from_index(i: u8) -> Self {
+        Self::KNOWN
+            .get(i as usize)
+            .copied()
+            .unwrap_or(Rarity::Unknown(i))
+    }
+}
+
+impl Element {
+    /// Variants this build knows, in wire order
+    pub const KNOWN: [Element; 5] = [
+        Element::Fire,
+        Element::Water,
+        Element::Earth,
+        Element::Air,
+        Element::Electric,
+    ];
+
+    pub fn index(self) -> u8 {
+        match self {
+            Element::Fire => 0,
+            Element::Water => 1,
+            Element::Earth => 2,
+            Element::Air => 3,
+            Element::Electric => 4,
+            Element::Unknown(i) => i,
+        }
+    }
+
+    pub fn from_index(i: u8) -> Self {
+        Self::KNOWN
+            .get(i as usize)
+            .copied()
+            .unwrap_or(Element::Unknown(i))
+    }
+}
+
+impl Archetype {
+    /// Variants this build knows, in wire order
+    pub const KNOWN: [Archetype; 4] = [
+        Archetype::Tank,
+        Archetype::Assassin,
+        Archetype::Mage,
+        Archetype::Beast,
+    ];
+
+    pub fn index(self) -> u8 {
+        match self {
+            Archetype::Tank => 0,
+            Archetype::Assassin => 1,
+            Archetype::Mage => 2,
+            Archetype::Beast => 3,
+            Archetype::Unknown(i) => i,
+        }
+    }
+
+    pub fn from_index(i: u8) -> Self {
+        Self::KNOWN
+            .get(i as usize)
+            .copied()
+            .unwrap_or(Archetype::Unknown(i))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -36,6 +139,15 @@ pub struct Stats {
     pub luck: i16, // 0..=20
 }
 
+/// How a monster was generated, so it can be regenerated and audited
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Provenance {
+    /// Seed passed to [`crate::generate_monster`]
+    pub seed: [u8; 32],
+    /// [`crate::GENERATOR_VERSION`] of the generator that ran
+    pub generator: u16,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Monster {
     pub v: u8, // version for forwards-compat
@@ -45,29 +157,347 @@ pub struct Monster {
     pub element: Element,
     pub archetype: Archetype,
     pub stats: Stats,
+    /// Present on locally generated monsters; not carried by share codes
+    #[serde(default)]
+    pub provenance: Option<Provenance>,
 }
 
-impl Monster {
-    pub fn validate(&self) -> Result<(), String> {
-        let s = &self.stats;
-        if !(0..=999).contains(&s.hp) {
-            return Err("hp out of range".into());
-        }
-        if !(0..=999).contains(&s.atk) {
-            return Err("atk out of range".into());
-        }
-        if !(0..=999).contains(&s.def) {
-            return Err("def out of range".into());
-        }
-        if !(0..=999).contains(&s.spd) {
-            return Err("spd out of range".into());
-        }
-        if !(0..=50).contains(&s.crit) {
-            return Err("crit out of range".into());
+/// Longest monster name we accept, in characters
+pub const MAX_NAME_LEN: usize = 24;
+
+/// Reason a monster failed [`Monster::validate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidateError {
+    /// A stat is outside the range the game allows for it
+    StatOutOfRange {
+        stat: &'static str,
+        value: i16,
+        allowed: RangeInclusive<i16>,
+    },
+    /// The name is empty or longer than [`MAX_NAME_LEN`]
+    NameLength { len: usize, max: usize },
+    /// The name contains a character outside letters, digits, space, `-` and `'`
+    NameCharacter(char),
+    /// The name starts or ends with a space, or has two in a row
+    NameSpacing,
+    /// A stat is below the floor the generator guarantees for this element
+    BelowMinimum {
+        stat: &'static str,
+        value: i16,
+        min: i16,
+    },
+    /// The stat total can't be reached by any roll for this rarity
+    TotalOutOfBudget {
+        rarity: Rarity,
+        total: i16,
+        allowed: RangeInclusive<i16>,
+    },
+    /// The total fits, but no generator run spreads points this way
+    ImpossibleDistribution { rarity: Rarity },
+    /// An enum index above [`MAX_ENUM_INDEX`], which no share code can carry
+    IndexOutOfRange { field: &'static str, index: u8 },
+    /// An `Unknown` variant holding the index of one this build knows
+    NonCanonicalIndex { field: &'static str, index: u8 },
+}
+
+impl fmt::Display for ValidateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidateError::StatOutOfRange {
+                stat,
+                value,
+                allowed,
+            } => write!(
+                f,
+                "{stat} out of range: {value} (allowed {}..={})",
+                allowed.start(),
+                allowed.end()
+            ),
+            ValidateError::NameLength { len, max } => {
+                write!(f, "name must be 1..={max} characters, got {len}")
+            }
+            ValidateError::NameCharacter(c) => write!(f, "name contains {c:?}"),
+            ValidateError::NameSpacing => write!(f, "name has stray spaces"),
+            ValidateError::BelowMinimum { stat, value, min } => {
+                write!(f, "{stat} below minimum: {value} (at least {min})")
+            }
+            ValidateError::TotalOutOfBudget {
+                rarity,
+                total,
+                allowed,
+            } => write!(
+                f,
+                "stat total {total} impossible for {rarity:?} (allowed {}..={})",
+                allowed.start(),
+                allowed.end()
+            ),
+            ValidateError::ImpossibleDistribution { rarity } => {
+                write!(f, "stat distribution impossible for {rarity:?}")
+            }
+            ValidateError::IndexOutOfRange { field, index } => {
+                write!(
+                    f,
+                    "{field} index {index} out of range (max {MAX_ENUM_INDEX})"
+                )
+            }
+            ValidateError::NonCanonicalIndex { field, index } => {
+                write!(f, "{field} index {index} is a known variant marked unknown")
+            }
         }
-        if !(0..=20).contains(&s.luck) {
-            return Err("luck out of range".into());
+    }
+}
+
+impl std::error::Error for ValidateError {}
+
+impl Monster {
+    /// Schema version of the in-memory struct, stored in [`Monster::v`]
+    pub const VERSION: u8 = 2;
+
+    pub fn validate(&self) -> Result<(), ValidateError> {
+        check_name(&self.name)?;
+        for (field, index, canonical) in [
+            (
+                "rarity",
+                self.rarity.index(),
+                Rarity::from_index(self.rarity.index()) == self.rarity,
+            ),
+            (
+                "element",
+                self.element.index(),
+                Element::from_index(self.element.index()) == self.element,
+            ),
+            (
+                "archetype",
+                self.archetype.index(),
+                Archetype::from_index(self.archetype.index()) == self.archetype,
+            ),
+        ] {
+            if index > MAX_ENUM_INDEX {
+                return Err(ValidateError::IndexOutOfRange { field, index });
+            }
+            // `Unknown(1)` would get the loose unknown budget yet play as Rare
+            if !canonical {
+                return Err(ValidateError::NonCanonicalIndex { field, index });
+            }
         }
+        let s = &self.stats;
+        check_stat("hp", s.hp, 0..=999)?;
+        check_stat("atk", s.atk, 0..=999)?;
+        check_stat("def", s.def, 0..=999)?;
+        check_stat("spd", s.spd, 0..=999)?;
+        check_stat("crit", s.crit, 0..=50)?;
+        check_stat("luck", s.luck, 0..=20)?;
+        StatBudget::for_monster(self).check(s)
+    }
+}
+
+/// Name policy, shared with team names in team share codes
+pub(crate) fn check_name(name: &str) -> Result<(), ValidateError> {
+    let len = name.chars().count();
+    if len == 0 || len > MAX_NAME_LEN {
+        return Err(ValidateError::NameLength {
+            len,
+            max: MAX_NAME_LEN,
+        });
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, ' ' | '-' | '\'')))
+    {
+        return Err(ValidateError::NameCharacter(c));
+    }
+    if name.starts_with(' ') || name.ends_with(' ') || name.contains("  ") {
+        return Err(ValidateError::NameSpacing);
+    }
+    Ok(())
+}
+
+fn check_stat(
+    stat: &'static str,
+    value: i16,
+    allowed: RangeInclusive<i16>,
+) -> Result<(), ValidateError> {
+    if allowed.contains(&value) {
         Ok(())
+    } else {
+        Err(ValidateError::StatOutOfRange {
+            stat,
+            value,
+            allowed,
+        })
+    }
+}
+
+/// Serde for the enums that carry an `Unknown` variant.
+///
+/// Human-readable formats (the JSON save file) use the variant name, or the
+/// bare index for unknown values. Binary formats use the index as a `u32`,
+/// the same bytes the derived impl wrote into `QRM1` codes.
+trait OpenEnum: Copy + 'static {
+    const NAMES: &'static [&'static str];
+    fn index(self) -> u8;
+    fn from_index(i: u8) -> Self;
+}
+
+impl OpenEnum for Rarity {
+    const NAMES: &'static [&'static str] = &["Common", "Rare", "Epic", "Legendary"];
+    fn index(self) -> u8 {
+        Rarity::index(self)
+    }
+    fn from_index(i: u8) -> Self {
+        Rarity::from_index(i)
+    }
+}
+
+impl OpenEnum for Element {
+    const NAMES: &'static [&'static str] = &["Fire", "Water", "Earth", "Air", "Electric"];
+    fn index(self) -> u8 {
+        Element::index(self)
+    }
+    fn from_index(i: u8) -> Self {
+        Element::from_index(i)
+    }
+}
+
+impl OpenEnum for Archetype {
+    const NAMES: &'static [&'static str] = &["Tank", "Assassin", "Mage", "Beast"];
+    fn index(self) -> u8 {
+        Archetype::index(self)
+    }
+    fn from_index(i: u8) -> Self {
+        Archetype::from_index(i)
+    }
+}
+
+fn serialize_open<T: OpenEnum, S: Serializer>(value: T, s: S) -> Result<S::Ok, S::Error> {
+    let index = value.index();
+    if !s.is_human_readable() {
+        return s.serialize_u32(u32::from(index));
+    }
+    match T::NAMES.get(index as usize) {
+        Some(name) => s.serialize_str(name),
+        None => s.serialize_u8(index),
+    }
+}
+
+fn deserialize_open<'de, T: OpenEnum, D: Deserializer<'de>>(d: D) -> Result<T, D::Error> {
+    if d.is_human_readable() {
+        d.deserialize_any(OpenEnumVisitor(PhantomData))
+    } else {
+        d.deserialize_u32(OpenEnumVisitor(PhantomData))
+    }
+}
+
+struct OpenEnumVisitor<T>(PhantomData<T>);
+
+impl<T: OpenEnum> Visitor<'_> for OpenEnumVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "one of {:?} or an index up to 255", T::NAMES)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<T, E> {
+        u8::try_from(v)
+            .map(T::from_index)
+            .map_err(|_| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+        T::NAMES
+            .iter()
+            .position(|n| *n == v)
+            .map(|i| T::from_index(i as u8))
+            .ok_or_else(|| E::unknown_variant(v, T::NAMES))
+    }
+}
+
+impl Serialize for Rarity {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        serialize_open(*self, s)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rarity {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        deserialize_open(d)
+    }
+}
+
+impl Serialize for Element {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        serialize_open(*self, s)
+    }
+}
+
+impl<'de> Deserialize<'de> for Element {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        deserialize_open(d)
+    }
+}
+
+impl Serialize for Archetype {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        serialize_open(*self, s)
+    }
+}
+
+impl<'de> Deserialize<'de> for Archetype {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        deserialize_open(d)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_variants_round_trip() {
+        for i in 0..=u8::MAX {
+            let e = Element::from_index(i);
+            assert_eq!(e.index(), i);
+            let json = serde_json::to_string(&e).unwrap();
+            assert_eq!(serde_json::from_str::<Element>(&json).unwrap(), e);
+            let bytes = bincode::serialize(&e).unwrap();
+            assert_eq!(bytes, u32::from(i).to_le_bytes());
+            assert_eq!(bincode::deserialize::<Element>(&bytes).unwrap(), e);
+        }
+        assert_eq!(serde_json::to_string(&Rarity::Epic).unwrap(), "\"Epic\"");
+        assert_eq!(serde_json::to_string(&Archetype::Unknown(9)).unwrap(), "9");
+        assert!(serde_json::from_str::<Archetype>("\"Wizard\"").is_err());
+    }
+
+    #[test]
+    fn test_known_index_as_unknown() {
+        // serde always goes through `from_index`, so it comes back canonical
+        let bytes = bincode::serialize(&Rarity::Unknown(1)).unwrap();
+        assert_eq!(
+            bincode::deserialize::<Rarity>(&bytes).unwrap(),
+            Rarity::Rare
+        );
+        let json = serde_json::to_string(&Element::Unknown(2)).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Element>(&json).unwrap(),
+            Element::Earth
+        );
+        assert_eq!(
+            serde_json::from_str::<Archetype>("3").unwrap(),
+            Archetype::Beast
+        );
+
+        // built by hand it's a second spelling of a known variant
+        let mut mon = crate::generate_monster([1; 32]);
+        assert_eq!(mon.validate(), Ok(()));
+        mon.rarity = Rarity::Unknown(mon.rarity.index());
+        assert_eq!(
+            mon.validate(),
+            Err(ValidateError::NonCanonicalIndex {
+                field: "rarity",
+                index: mon.rarity.index(),
+            })
+        );
+        mon.rarity = Rarity::Unknown(9);
+        assert_eq!(mon.validate(), Ok(()));
     }
 }
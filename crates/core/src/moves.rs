@@ -0,0 +1,187 @@
+//! Per-monster move sets.
+//!
+//! Every monster has one move of each [`MoveKind`]. Names and numbers come
+//! from its archetype, element and a hash of its ID, so the same monster
+//! always has the same moves, including ones imported from share codes.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Archetype, Element, Monster};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MoveKind {
+    /// Plain attack with a small bonus to the attack roll
+    Attack,
+    /// Adds to DEF until the monster's next move
+    Guard,
+    /// Restores HP, up to the monster's starting HP
+    Heal,
+    /// Element attack with a big bonus to the attack roll
+    Special,
+}
+
+impl MoveKind {
+    pub const ALL: [MoveKind; 4] = [
+        MoveKind::Attack,
+        MoveKind::Guard,
+        MoveKind::Heal,
+        MoveKind::Special,
+    ];
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Move {
+    pub kind: MoveKind,
+    pub name: String,
+    /// Attack roll bonus, DEF bonus or HP healed, depending on `kind`
+    pub power: i16,
+    /// Times it can be used per battle; `None` for unlimited
+    pub uses: Option<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveSet {
+    pub attack: Move,
+    pub guard: Move,
+    pub heal: Move,
+    pub special: Move,
+}
+
+impl MoveSet {
+    pub fn get(&self, kind: MoveKind) -> &Move {
+        match kind {
+            MoveKind::Attack => &self.attack,
+            MoveKind::Guard => &self.guard,
+            MoveKind::Heal => &self.heal,
+            MoveKind::Special => &self.special,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Move> {
+        MoveKind::ALL.into_iter().map(|k| self.get(k))
+    }
+}
+
+impl Monster {
+    /// This monster's moves, derived from its ID, archetype and element
+    pub fn move_set(&self) -> MoveSet {
+        let mut input = [0u8; 18];
+        input[..16].copy_from_slice(self.id.as_bytes());
+        input[16] = self.archetype.index();
+        input[17] = self.element.index();
+        let hash = blake3::derive_key("qrmonsters move set v1", &input);
+
+        let names = archetype_names(self.archetype);
+        // pick one of two names, and a +0/+1 tweak, per move
+        let pick = |i: usize| (hash[i] & 1) as usize;
+        let tweak = |i: usize| i16::from(hash[i] >> 7);
+        let [attack, guard, heal, special] = archetype_numbers(self.archetype);
+
+        MoveSet {
+            attack: Move {
+                kind: MoveKind::Attack,
+                name: names.attack[pick(0)].into(),
+                power: attack.0 + tweak(0),
+                uses: None,
+            },
+            guard: Move {
+                kind: MoveKind::Guard,
+                name: names.guard[pick(1)].into(),
+                power: guard.0 + tweak(1),
+                uses: None,
+            },
+            heal: Move {
+                kind: MoveKind::Heal,
+                name: names.heal[pick(2)].into(),
+                power: heal.0 + tweak(2),
+                uses: heal.1,
+            },
+            special: Move {
+                kind: MoveKind::Special,
+                name: element_special(self.element)[pick(3)].into(),
+                power: special.0 + tweak(3),
+                uses: special.1,
+            },
+        }
+    }
+}
+
+struct Names {
+    attack: [&'static str; 2],
+    guard: [&'static str; 2],
+    heal: [&'static str; 2],
+}
+
+fn archetype_names(archetype: Archetype) -> Names {
+    match archetype {
+        Archetype::Tank => Names {
+            attack: ["Slam", "Bash"],
+            guard: ["Brace", "Shell Up"],
+            heal: ["Fortify", "Regenerate"],
+        },
+        Archetype::Assassin => Names {
+            attack: ["Slash", "Backstab"],
+            guard: ["Shadow Step", "Feint"],
+            heal: ["Bandage", "Catch Breath"],
+        },
+        Archetype::Mage => Names {
+            attack: ["Arcane Bolt", "Hex"],
+            guard: ["Ward", "Mirror Shield"],
+            heal: ["Mend", "Meditate"],
+        },
+        Archetype::Beast => Names {
+            attack: ["Bite", "Maul"],
+            guard: ["Hunker", "Bristle"],
+            heal: ["Lick Wounds", "Rest"],
+        },
+        Archetype::Unknown(_) => Names {
+            attack: ["Strike", "Strike"],
+            guard: ["Guard", "Guard"],
+            heal: ["Recover", "Recover"],
+        },
+    }
+}
+
+/// (power, uses) for attack, guard, heal and special
+fn archetype_numbers(archetype: Archetype) -> [(i16, Option<u8>); 4] {
+    match archetype {
+        Archetype::Tank => [(0, None), (6, None), (6, Some(2)), (3, Some(2))],
+        Archetype::Assassin => [(2, None), (3, None), (3, Some(1)), (5, Some(2))],
+        Archetype::Mage => [(0, None), (3, None), (5, Some(2)), (6, Some(3))],
+        Archetype::Beast => [(1, None), (4, None), (4, Some(2)), (4, Some(2))],
+        Archetype::Unknown(_) => [(0, None), (4, None), (4, Some(1)), (4, Some(1))],
+    }
+}
+
+fn element_special(element: Element) -> [&'static str; 2] {
+    match element {
+        Element::Fire => ["Flame Burst", "Inferno"],
+        Element::Water => ["Tidal Crash", "Riptide"],
+        Element::Earth => ["Rockslide", "Quake"],
+        Element::Air => ["Gale", "Cyclone"],
+        Element::Electric => ["Thunderbolt", "Static Shock"],
+        Element::Unknown(_) => ["Strange Power", "Strange Power"],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_monster;
+
+    #[test]
+    fn test_move_sets_are_stable() {
+        let mon = generate_monster([8u8; 32]);
+        let set = mon.move_set();
+        assert_eq!(set, mon.move_set());
+        assert!(set.iter().map(|m| m.kind).eq(MoveKind::ALL));
+
+        // share codes drop provenance but keep the ID, so moves survive
+        let shared = crate::decode_share(&crate::encode_share(&mon).unwrap()).unwrap();
+        assert_eq!(shared.move_set(), set);
+
+        let mut other = mon.clone();
+        other.archetype = Archetype::Unknown(9);
+        assert_eq!(other.move_set().attack.name, "Strike");
+    }
+}
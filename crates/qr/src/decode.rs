@@ -54,7 +54,6 @@ pub fn decode_from_image(img: DynamicImage) -> Result<String, QrDecodeError> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::encode::qr_svg;
 
     // Note: Full round-trip test would require SVG rendering to image,
     // which is complex. In practice, test with actual image files.
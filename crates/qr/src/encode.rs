@@ -34,6 +34,23 @@ pub fn qr_svg(data: &str, size: u32) -> Result<String, QrEncodeError> {
     Ok(svg)
 }
 
+/// Whether `data` only uses the QR alphanumeric character set
+/// (`0-9`, `A-Z`, space and `$%*+-./:`).
+///
+/// The encoder picks the densest mode on its own, so such data is stored at
+/// 5.5 bits per character instead of 8 and produces a smaller code.
+pub fn is_alphanumeric(data: &str) -> bool {
+    data.chars()
+        .all(|c| c.is_ascii_digit() || c.is_ascii_uppercase() || " $%*+-./:".contains(c))
+}
+
+/// Side length, in modules, of the QR code that would be generated for `data`
+pub fn qr_width(data: &str) -> Result<usize, QrEncodeError> {
+    let code = QrCode::new(data.as_bytes())
+        .map_err(|e| QrEncodeError(e.to_string()))?;
+    Ok(code.width())
+}
+
 /// Generate an SVG string with custom colors
 pub fn qr_svg_colored(
     data: &str,
@@ -65,4 +82,24 @@ mod tests {
         assert!(svg.contains("<svg"));
         assert!(svg.contains("</svg>"));
     }
+
+    /// Golden share codes from the core crate, one per layout
+    const FIXTURES: &str = include_str!("../../core/fixtures/share_codes.txt");
+
+    fn fixture(version: &str) -> &'static str {
+        FIXTURES
+            .lines()
+            .find(|l| l.starts_with(version))
+            .expect("fixture present")
+    }
+
+    #[test]
+    fn test_alphanumeric_codes_are_smaller() {
+        // a QRM1 (base64) and a QRM2 (base-44) code for a monster each
+        let v1 = fixture("QRM1:");
+        let v2 = fixture("QRM2:");
+        assert!(!is_alphanumeric(v1));
+        assert!(is_alphanumeric(v2));
+        assert!(qr_width(v2).unwrap() < qr_width(v1).unwrap());
+    }
 }
@@ -6,5 +6,5 @@
 pub mod encode;
 pub mod decode;
 
-pub use encode::{qr_svg, qr_svg_colored, QrEncodeError};
+pub use encode::{is_alphanumeric, qr_svg, qr_svg_colored, qr_width, QrEncodeError};
 pub use decode::{decode_qr, decode_from_image, QrDecodeError};